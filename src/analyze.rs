@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use crate::{finger::Finger, layout::Layout};
+
+/// Width of a key, in millimeters, to turn travel in keys into meters.
+const KEY_PITCH: f64 = 19.0;
+
+/// How a text would be typed on a layout.
+#[derive(Debug, Default)]
+pub struct Analysis {
+    /// Key strokes, each char being one.
+    pub n_keys: usize,
+    /// Chars that are not on the layout.
+    pub n_unknown: usize,
+    /// Distance covered by the fingers, in keys.
+    pub travel: f64,
+    /// Key strokes per finger.
+    pub fingers: BTreeMap<Finger, usize>,
+    /// Key strokes per row, 0 being the number row.
+    pub rows: BTreeMap<i16, usize>,
+    /// Pairs of consecutive key strokes by the fingers, without the thumbs.
+    pub n_pairs: usize,
+    /// Pairs typed by alternating hands.
+    pub n_alternations: usize,
+    /// Pairs of different keys typed by the same finger.
+    pub n_same_finger: usize,
+}
+
+/// Where a finger rests, on the home row.
+fn home(finger: Finger) -> (i16, i16) {
+    (finger.column(), 2)
+}
+
+fn distance((col_a, row_a): (i16, i16), (col_b, row_b): (i16, i16)) -> f64 {
+    f64::from(col_a - col_b).hypot(f64::from(row_a - row_b))
+}
+
+impl Analysis {
+    /// Analyze typing `text` on `layout`.
+    ///
+    /// A finger moves from its home key to each key it types, and goes back home as soon as
+    /// another finger types. The thumbs do not travel.
+    #[must_use]
+    pub fn new(text: &str, layout: &Layout) -> Self {
+        let mut analysis = Self::default();
+        // last finger used and where it is
+        let mut last: Option<(Finger, (i16, i16))> = None;
+        for c in text.chars() {
+            let Some((col, row, _)) = layout.position(c) else {
+                analysis.n_unknown += 1;
+                continue;
+            };
+            let Some(f) = layout.finger(c) else {
+                analysis.n_unknown += 1;
+                continue;
+            };
+            let key = (col, row);
+            analysis.n_keys += 1;
+            *analysis.fingers.entry(f).or_default() += 1;
+            *analysis
+                .rows
+                .entry(if f.is_thumb() { -1 } else { row })
+                .or_default() += 1;
+            if f.is_thumb() {
+                if let Some((last_f, last_key)) = last.take() {
+                    analysis.travel += distance(last_key, home(last_f));
+                }
+                continue;
+            }
+
+            match last {
+                Some((last_f, last_key)) if last_f == f => {
+                    analysis.travel += distance(last_key, key);
+                    if last_key != key {
+                        analysis.n_same_finger += 1;
+                    }
+                }
+                Some((last_f, last_key)) => {
+                    analysis.travel += distance(last_key, home(last_f)) + distance(home(f), key);
+                    if last_f.hand() != f.hand() {
+                        analysis.n_alternations += 1;
+                    }
+                }
+                None => analysis.travel += distance(home(f), key),
+            }
+            if last.is_some() {
+                analysis.n_pairs += 1;
+            }
+            last = Some((f, key));
+        }
+        if let Some((last_f, last_key)) = last {
+            analysis.travel += distance(last_key, home(last_f));
+        }
+        analysis
+    }
+
+    /// Travel in meters.
+    #[must_use]
+    pub fn travel_meters(&self) -> f64 {
+        self.travel * KEY_PITCH / 1000.0
+    }
+
+    /// Share of the key strokes, in percent.
+    #[must_use]
+    pub fn percent(&self, n: usize) -> f64 {
+        ratio(n, self.n_keys)
+    }
+
+    /// Share of the pairs typed by alternating hands, in percent.
+    #[must_use]
+    pub fn alternation(&self) -> f64 {
+        ratio(self.n_alternations, self.n_pairs)
+    }
+
+    /// Share of the pairs of different keys typed by the same finger, in percent.
+    #[must_use]
+    pub fn same_finger(&self) -> f64 {
+        ratio(self.n_same_finger, self.n_pairs)
+    }
+}
+
+fn ratio(n: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        n as f64 * 100.0 / total as f64
+    }
+}
+
+const ROWS: &[(i16, &str)] = &[
+    (0, "number row"),
+    (1, "top row"),
+    (2, "home row"),
+    (3, "bottom row"),
+    (-1, "space"),
+];
+
+/// A table comparing the analyses of a text on several layouts, one column per layout.
+#[must_use]
+pub fn report(analyses: &[(&str, Analysis)]) -> Vec<String> {
+    let mut table: Vec<(String, Vec<String>)> = Vec::new();
+    let mut add = |name: &str, cell: &dyn Fn(&Analysis) -> String| {
+        let cells = analyses.iter().map(|(_, a)| cell(a)).collect();
+        table.push((name.to_string(), cells));
+    };
+    add("key strokes", &|a| a.n_keys.to_string());
+    add("not on layout", &|a| a.n_unknown.to_string());
+    add("finger travel", &|a| format!("{:.1} m", a.travel_meters()));
+    add("hand alternation", &|a| format!("{:.1}%", a.alternation()));
+    add("same finger bigrams", &|a| {
+        format!("{:.2}%", a.same_finger())
+    });
+    add("", &|_| String::new());
+    for finger in Finger::ALL {
+        add(&finger.name(), &|a| {
+            format!(
+                "{:.1}%",
+                a.percent(a.fingers.get(&finger).copied().unwrap_or(0))
+            )
+        });
+    }
+    add("", &|_| String::new());
+    for &(row, name) in ROWS {
+        add(name, &|a| {
+            format!("{:.1}%", a.percent(a.rows.get(&row).copied().unwrap_or(0)))
+        });
+    }
+
+    let mut lines = vec![format!(
+        "{:<22}{}",
+        "",
+        analyses
+            .iter()
+            .map(|(name, _)| format!("{name:>12}"))
+            .collect::<String>()
+    )];
+    for (name, cells) in table {
+        if name.is_empty() {
+            lines.push(String::new());
+            continue;
+        }
+        let cells: String = cells.iter().map(|cell| format!("{cell:>12}")).collect();
+        lines.push(format!("{name:<22}{cells}"));
+    }
+    lines
+}
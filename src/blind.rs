@@ -0,0 +1,77 @@
+use crate::stat::Stat;
+
+/// Widest line of errors on the results screen.
+const LINE_WIDTH: usize = 60;
+
+/// A char as it is shown in the errors, with whitespace made visible.
+fn visible(c: char) -> char {
+    match c {
+        ' ' => '␣',
+        '\n' => '⏎',
+        '\t' => '⇥',
+        c => c,
+    }
+}
+
+/// Words of `text` that were mistyped, each as expected and as typed.
+///
+/// A miss on the whitespace after a word counts against the word, and shows the whitespace.
+#[must_use]
+pub fn errors(text: &str, stat: &Stat) -> Vec<(String, String)> {
+    let text: Vec<char> = text.chars().collect();
+    let mut typed: Vec<Option<char>> = vec![None; text.len()];
+    for stroke in &stat.keystrokes {
+        if let (Some(position), Some(false)) = (stroke.position, stroke.hit) {
+            if let Some(slot) = typed.get_mut(position) {
+                *slot = Some(stroke.key);
+            }
+        }
+    }
+
+    let mut errors = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        if text[start].is_whitespace() {
+            start += 1;
+            continue;
+        }
+        let mut end = start;
+        while end < text.len() && !text[end].is_whitespace() {
+            end += 1;
+        }
+        if end < text.len() && typed[end].is_some() {
+            end += 1;
+        }
+        if typed[start..end].iter().any(Option::is_some) {
+            let expected = text[start..end].iter().copied().map(visible).collect();
+            let actual = (start..end)
+                .map(|i| visible(typed[i].unwrap_or(text[i])))
+                .collect();
+            errors.push((expected, actual));
+        }
+        start = end;
+    }
+    errors
+}
+
+/// Lines for the results screen, revealing what was mistyped.
+#[must_use]
+pub fn summary(errors: &[(String, String)]) -> Vec<String> {
+    if errors.is_empty() {
+        return vec!["no errors, well done!".to_string()];
+    }
+    let mut lines = vec![format!("{} mistyped words:", errors.len())];
+    let mut line = String::new();
+    for (expected, actual) in errors {
+        let error = format!("{expected}→{actual}");
+        if !line.is_empty() && line.chars().count() + error.chars().count() >= LINE_WIDTH {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push_str("  ");
+        }
+        line.push_str(&error);
+    }
+    lines.push(line);
+    lines
+}
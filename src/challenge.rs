@@ -0,0 +1,195 @@
+use std::io::Result;
+use std::time::Duration;
+
+use crate::{
+    date::{self, Date},
+    stat::Stat,
+    storage::Table,
+};
+
+/// The high score file, which is only ever appended to.
+const TABLE: Table = Table {
+    file: "scores.tsv",
+    kind: "scores",
+    version: 1,
+    columns: &["challenge", "time", "score", "wpm", "accuracy", "source"],
+};
+
+/// Chars to type before the accuracy is checked, so that an early miss does not end it all.
+const GRACE_CHARS: usize = 20;
+
+/// High scores shown on the results screen.
+pub const TOP: usize = 5;
+
+/// A rule a session has to keep to, failing as soon as it is broken.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Challenge {
+    /// Ends on the first miss, scored by the chars typed until then.
+    SuddenDeath,
+    /// Fails below an accuracy in percent, scored by speed.
+    MinAccuracy(f64),
+    /// Fails below a speed in wpm at the end, scored by accuracy.
+    MinWpm(f64),
+}
+
+impl Challenge {
+    /// Name of the challenge, each has its own high score table.
+    #[must_use]
+    pub fn name(self) -> String {
+        match self {
+            Challenge::SuddenDeath => "sudden death".to_string(),
+            Challenge::MinAccuracy(min) => format!("accuracy {min}%"),
+            Challenge::MinWpm(min) => format!("speed {min} wpm"),
+        }
+    }
+
+    /// Why the challenge is failed, if it is, either while typing or once `finished`.
+    #[must_use]
+    pub fn check(self, stat: &Stat, elapsed: Duration, finished: bool) -> Option<String> {
+        match self {
+            Challenge::SuddenDeath if stat.n_wrong > 0 => {
+                Some(format!("missed after {} right chars", stat.n_correct))
+            }
+            Challenge::MinAccuracy(min)
+                if (finished || stat.n_correct + stat.n_wrong >= GRACE_CHARS)
+                    && stat.accuracy() < min =>
+            {
+                Some(format!(
+                    "accuracy dropped to {:.1}%, below {min}%",
+                    stat.accuracy()
+                ))
+            }
+            Challenge::MinWpm(min) if finished && stat.wpm(elapsed) < min => {
+                Some(format!("{:.0} wpm is below {min} wpm", stat.wpm(elapsed)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Score of a session, unless it failed a challenge where only passing counts.
+    #[must_use]
+    pub fn score(self, stat: &Stat, elapsed: Duration, failed: bool) -> Option<f64> {
+        match self {
+            Challenge::SuddenDeath => Some(stat.n_correct as f64),
+            Challenge::MinAccuracy(_) if !failed => Some(stat.wpm(elapsed)),
+            Challenge::MinWpm(_) if !failed => Some(stat.accuracy()),
+            _ => None,
+        }
+    }
+
+    fn format_score(self, score: f64) -> String {
+        match self {
+            Challenge::SuddenDeath => format!("{score:.0} chars"),
+            Challenge::MinAccuracy(_) => format!("{score:.0} wpm"),
+            Challenge::MinWpm(_) => format!("{score:.1}%"),
+        }
+    }
+}
+
+/// A session that made it into a high score table.
+#[derive(Debug, Clone)]
+pub struct Score {
+    /// Name of the challenge.
+    pub challenge: String,
+    /// Seconds since the unix epoch when the session ended.
+    pub time: u64,
+    pub score: f64,
+    pub wpm: f64,
+    pub accuracy: f64,
+    /// Where the text came from, e.g. `words:english` or a file path.
+    pub source: String,
+}
+
+impl Score {
+    #[must_use]
+    pub fn new(
+        challenge: Challenge,
+        score: f64,
+        stat: &Stat,
+        elapsed: Duration,
+        source: &str,
+    ) -> Self {
+        Self {
+            challenge: challenge.name(),
+            time: date::now(),
+            score,
+            wpm: stat.wpm(elapsed),
+            accuracy: stat.accuracy(),
+            source: source.to_string(),
+        }
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.challenge.clone(),
+            self.time.to_string(),
+            format!("{:.2}", self.score),
+            format!("{:.2}", self.wpm),
+            format!("{:.2}", self.accuracy),
+            self.source.clone(),
+        ]
+    }
+
+    fn from_fields(fields: &[String]) -> Option<Self> {
+        let [challenge, time, score, wpm, accuracy, source] = fields else {
+            return None;
+        };
+        Some(Self {
+            challenge: challenge.clone(),
+            time: time.parse().ok()?,
+            score: score.parse().ok()?,
+            wpm: wpm.parse().ok()?,
+            accuracy: accuracy.parse().ok()?,
+            source: source.clone(),
+        })
+    }
+}
+
+/// Add a score to the high score file.
+pub fn append(score: &Score) -> Result<()> {
+    TABLE.append(&[score.fields()])
+}
+
+/// All scores of the high score file, oldest first.
+pub fn load() -> Result<Vec<Score>> {
+    Ok(TABLE
+        .load()?
+        .rows
+        .iter()
+        .filter_map(|row| Score::from_fields(row))
+        .collect())
+}
+
+/// Lines of the high score table of a challenge, best first, marking the score `latest`.
+#[must_use]
+pub fn table(
+    challenge: Challenge,
+    scores: &[Score],
+    n: usize,
+    latest: Option<&Score>,
+) -> Vec<String> {
+    let name = challenge.name();
+    let mut best: Vec<&Score> = scores.iter().filter(|s| s.challenge == name).collect();
+    best.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.time.cmp(&b.time)));
+    if best.is_empty() {
+        return vec![format!("no high scores for {name} yet")];
+    }
+    let mut lines = vec![format!("high scores for {name}:")];
+    for (i, score) in best.into_iter().take(n).enumerate() {
+        // the score is rounded in the file, but no two end in the same second
+        let mark = if latest.is_some_and(|l| l.time == score.time) {
+            " <"
+        } else {
+            ""
+        };
+        lines.push(format!(
+            "{:>2}. {:>10}  {:>4.0} wpm {:>5.1}%  {}{mark}",
+            i + 1,
+            challenge.format_score(score.score),
+            score.wpm,
+            score.accuracy,
+            Date::from_timestamp(score.time),
+        ));
+    }
+    lines
+}
@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use crate::date::Date;
+
+/// Bits of the dots of a braille char, by column then row.
+const BRAILLE_DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// Blocks of increasing height, by eighths.
+const BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Shades of increasing density, for the heatmap.
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+/// Smallest and largest of `values`, apart by at least 1 so that a flat line is centered.
+#[must_use]
+pub fn range(values: &[f64]) -> (f64, f64) {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if !min.is_finite() {
+        (0.0, 1.0)
+    } else if max - min < 1.0 {
+        (min - 0.5, max + 0.5)
+    } else {
+        (min, max)
+    }
+}
+
+/// A line chart of `values` from left to right, in braille dots.
+#[must_use]
+pub fn line_chart(values: &[f64], width: usize, height: usize) -> Vec<String> {
+    let (n_cols, n_rows) = (width * 2, height * 4);
+    let mut dots = vec![vec![false; n_cols]; n_rows];
+    if n_cols > 0 && n_rows > 0 && !values.is_empty() {
+        let (min, max) = range(values);
+        let point = |i: usize| {
+            let x = if values.len() == 1 {
+                0
+            } else {
+                i * (n_cols - 1) / (values.len() - 1)
+            };
+            let y = ((values[i] - min) / (max - min) * (n_rows - 1) as f64).round() as usize;
+            (x, n_rows - 1 - y.min(n_rows - 1))
+        };
+        for i in 0..values.len() {
+            let (x, y) = point(i);
+            let (next_x, next_y) = if i + 1 < values.len() {
+                point(i + 1)
+            } else {
+                (x, y)
+            };
+            // join the points, filling the rows in between so that steep lines stay connected
+            let row_at = |col: usize| {
+                let t = if next_x == x {
+                    0.0
+                } else {
+                    (col - x) as f64 / (next_x - x) as f64
+                };
+                (y as f64 + (next_y as f64 - y as f64) * t).round() as usize
+            };
+            for col in x..=next_x {
+                let (a, b) = (row_at(col), row_at((col + 1).min(next_x)));
+                for row in dots.iter_mut().take(a.max(b) + 1).skip(a.min(b)) {
+                    row[col] = true;
+                }
+            }
+        }
+    }
+    (0..height)
+        .map(|row| {
+            (0..width)
+                .map(|col| {
+                    let mut bits = 0;
+                    for (dx, column) in BRAILLE_DOTS.iter().enumerate() {
+                        for (dy, bit) in column.iter().enumerate() {
+                            if dots[row * 4 + dy][col * 2 + dx] {
+                                bits |= bit;
+                            }
+                        }
+                    }
+                    char::from_u32(0x2800 + bits).unwrap_or(' ')
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A bar chart of `counts`, one bar per column, stretched or squeezed to `width` columns.
+#[must_use]
+pub fn histogram(counts: &[usize], width: usize, height: usize) -> Vec<String> {
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+    let bars: Vec<usize> = (0..width)
+        .map(|col| {
+            let count = counts
+                .get(col * counts.len() / width.max(1))
+                .copied()
+                .unwrap_or(0);
+            // height of the bar in eighths of a row
+            (count * height * 8).div_ceil(max)
+        })
+        .collect();
+    (0..height)
+        .map(|row| {
+            // rows from the top, each covering eight eighths
+            let floor = (height - 1 - row) * 8;
+            bars.iter()
+                .map(|&bar| BLOCKS[bar.saturating_sub(floor).min(8)])
+                .collect()
+        })
+        .collect()
+}
+
+/// A one line chart of `values`, averaged down to at most `width` chars.
+#[must_use]
+pub fn sparkline(values: &[f64], width: usize) -> String {
+    let n = values.len().min(width);
+    let columns: Vec<f64> = (0..n)
+        .map(|col| {
+            let (start, end) = (col * values.len() / n, (col + 1) * values.len() / n);
+            values[start..end].iter().sum::<f64>() / (end - start) as f64
+        })
+        .collect();
+    let max = columns.iter().copied().fold(0.0, f64::max);
+    columns
+        .iter()
+        .map(|&value| {
+            if max > 0.0 {
+                BLOCKS[((value / max * 8.0).round() as usize).clamp(1, 8)]
+            } else {
+                BLOCKS[1]
+            }
+        })
+        .collect()
+}
+
+/// A calendar of the last `weeks` weeks up to `today`, one row per day of the week from
+/// Monday and one column per week, shaded by the value of each day.
+#[must_use]
+pub fn heatmap(days: &BTreeMap<Date, f64>, today: Date, weeks: usize) -> Vec<String> {
+    let last = today.days();
+    // the first column starts on a Monday
+    let first = last - i64::from(today.weekday()) - (weeks as i64 - 1) * 7;
+    let max = days.values().copied().fold(0.0, f64::max);
+    (0..7)
+        .map(|weekday| {
+            (0..weeks as i64)
+                .map(|week| {
+                    let day = first + week * 7 + weekday;
+                    if day > last {
+                        return ' ';
+                    }
+                    match days.get(&Date::from_days(day)) {
+                        Some(&value) if value > 0.0 && max > 0.0 => {
+                            let shade = (value / max * 4.0).ceil() as usize;
+                            SHADES[shade.clamp(1, 4)]
+                        }
+                        _ => SHADES[0],
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
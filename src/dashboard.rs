@@ -0,0 +1,199 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::Result;
+use std::time::Duration;
+
+use crossterm::{
+    event::{read, Event, KeyCode, KeyEventKind, KeyModifiers},
+    style::Stylize,
+};
+
+use crate::{
+    chart::{self, range},
+    date::Date,
+    history::Record,
+    keylog::Entry,
+    screen::MainScreen,
+};
+
+/// Longest interval between key strokes in the histogram, longer ones are pauses.
+const MAX_INTERVAL: Duration = Duration::from_millis(1000);
+/// Width of a bin of the histogram.
+const BIN: Duration = Duration::from_millis(25);
+
+/// Room on the left of a chart for the axis labels.
+const LABEL_WIDTH: u16 = 7;
+
+/// Charts of the progress over the last sessions.
+pub struct Dashboard {
+    screen: MainScreen,
+    /// The last sessions, oldest first.
+    records: Vec<Record>,
+    /// Key strokes of those sessions.
+    entries: Vec<Entry>,
+    /// Practice time per day, in minutes, over the whole history.
+    days: BTreeMap<Date, f64>,
+}
+
+impl Dashboard {
+    /// A dashboard of the last `n` of `records`, with the practice time of all of them.
+    #[must_use]
+    pub fn new(records: &[Record], entries: Vec<Entry>, n: usize) -> Self {
+        let mut days = BTreeMap::new();
+        for record in records {
+            *days.entry(record.date()).or_insert(0.0) += record.elapsed / 60.0;
+        }
+        let records = records[records.len().saturating_sub(n)..].to_vec();
+        let ids: HashSet<usize> = records.iter().map(|r| r.id).collect();
+        let entries = entries
+            .into_iter()
+            .filter(|e| ids.contains(&e.session))
+            .collect();
+        Self {
+            screen: MainScreen::new(),
+            records,
+            entries,
+            days,
+        }
+    }
+
+    /// Show the charts until a key is pressed.
+    pub fn run(&mut self) -> Result<()> {
+        self.draw()?;
+        loop {
+            match read()? {
+                Event::Key(event) if event.kind != KeyEventKind::Release => {
+                    let control = event.modifiers == KeyModifiers::CONTROL;
+                    match event.code {
+                        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => return Ok(()),
+                        KeyCode::Char('c') if control => return Ok(()),
+                        _ => (),
+                    }
+                }
+                Event::Resize(width, height) => {
+                    self.screen.set_size(width, height);
+                    self.draw()?;
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn draw(&mut self) -> Result<()> {
+        self.screen.clear()?;
+        if self.records.is_empty() {
+            let message = "no sessions yet, practice a bit first";
+            self.screen.move_to(
+                self.screen.width.saturating_sub(message.len() as u16) / 2,
+                self.screen.height / 2,
+            )?;
+            self.screen.put(message.reset())?;
+            return self.screen.flush();
+        }
+
+        let width = self.screen.width.saturating_sub(3) / 2;
+        let height = self.screen.height.saturating_sub(2) / 2;
+        let chart_width = width.saturating_sub(LABEL_WIDTH + 1) as usize;
+        let chart_height = height.saturating_sub(3) as usize;
+        let n = self.records.len();
+
+        let wpm: Vec<f64> = self.records.iter().map(|r| r.wpm).collect();
+        let (min, max) = range(&wpm);
+        self.panel(
+            (1, 0),
+            &format!("speed over the last {n} sessions"),
+            &chart::line_chart(&wpm, chart_width, chart_height),
+            (format!("{max:.0} wpm"), format!("{min:.0} wpm")),
+            "oldest to latest",
+        )?;
+
+        let accuracy: Vec<f64> = self.records.iter().map(|r| r.accuracy).collect();
+        let (min, max) = range(&accuracy);
+        self.panel(
+            (width + 2, 0),
+            &format!("accuracy over the last {n} sessions"),
+            &chart::line_chart(&accuracy, chart_width, chart_height),
+            (format!("{max:.1}%"), format!("{min:.1}%")),
+            "oldest to latest",
+        )?;
+
+        let counts = self.intervals();
+        let max = counts.iter().copied().max().unwrap_or(0);
+        self.panel(
+            (1, height + 1),
+            "time between key strokes",
+            &chart::histogram(&counts, chart_width, chart_height),
+            (max.to_string(), "0".to_string()),
+            &format!(
+                "0{:>width$}",
+                format!("{} ms", MAX_INTERVAL.as_millis()),
+                width = chart_width.saturating_sub(1)
+            ),
+        )?;
+
+        let weeks = chart_width.min(53);
+        let mut calendar = chart::heatmap(&self.days, Date::today(), weeks);
+        calendar.truncate(chart_height);
+        let last_day = if calendar.len() == 7 { "Sun" } else { "" };
+        self.panel(
+            (width + 2, height + 1),
+            &format!("practice time over the last {weeks} weeks"),
+            &calendar,
+            ("Mon".to_string(), last_day.to_string()),
+            "less ·░▒▓█ more",
+        )?;
+
+        let hint = "Esc: quit";
+        self.screen.move_to(
+            self.screen.width.saturating_sub(hint.len() as u16) / 2,
+            self.screen.height - 1,
+        )?;
+        self.screen.put(hint.dark_grey())?;
+        self.screen.flush()
+    }
+
+    /// Draw a titled chart at `(x, y)`, with labels for the top and bottom of its axis.
+    fn panel(
+        &mut self,
+        (x, y): (u16, u16),
+        title: &str,
+        lines: &[String],
+        (top, bottom): (String, String),
+        footer: &str,
+    ) -> Result<()> {
+        let label_width = LABEL_WIDTH as usize;
+        self.screen.move_to(x + LABEL_WIDTH + 1, y)?;
+        self.screen.put(title.bold())?;
+        for (i, line) in lines.iter().enumerate() {
+            let label = if i == 0 {
+                top.as_str()
+            } else if i + 1 == lines.len() {
+                bottom.as_str()
+            } else {
+                ""
+            };
+            self.screen.move_to(x, y + 1 + i as u16)?;
+            self.screen
+                .put(format!("{label:>label_width$} ").dark_grey())?;
+            self.screen.put(line.as_str().green())?;
+        }
+        self.screen
+            .move_to(x + LABEL_WIDTH + 1, y + 1 + lines.len() as u16)?;
+        self.screen.put(footer.dark_grey())
+    }
+
+    /// Number of intervals between key strokes in each bin of the histogram.
+    fn intervals(&self) -> Vec<usize> {
+        let n_bins = (MAX_INTERVAL.as_millis() / BIN.as_millis()) as usize;
+        let mut counts = vec![0; n_bins];
+        for pair in self.entries.windows(2) {
+            if pair[0].session != pair[1].session {
+                continue;
+            }
+            let interval = pair[1].time.saturating_sub(pair[0].time);
+            if interval < MAX_INTERVAL {
+                counts[(interval.as_millis() / BIN.as_millis()) as usize] += 1;
+            }
+        }
+        counts
+    }
+}
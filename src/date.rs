@@ -0,0 +1,150 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+/// Seconds since the unix epoch.
+#[must_use]
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+impl Date {
+    #[must_use]
+    pub fn today() -> Self {
+        Self::from_timestamp(now())
+    }
+
+    #[must_use]
+    pub fn from_timestamp(secs: u64) -> Self {
+        Self::from_days((secs / 86400) as i64)
+    }
+
+    /// Date from days since the unix epoch.
+    #[must_use]
+    pub fn from_days(days: i64) -> Self {
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+
+    /// Days since the unix epoch.
+    #[must_use]
+    pub fn days(&self) -> i64 {
+        // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5
+            + i64::from(self.day)
+            - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// Day of the week, 0 for Monday.
+    #[must_use]
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday
+        (self.days() + 3).rem_euclid(7) as u32
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// Parse a date in the form of `YYYY-MM-DD`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("`{s}` is not a date in the form of YYYY-MM-DD");
+        let mut parts = s.splitn(3, '-');
+        let mut next = || {
+            parts
+                .next()
+                .and_then(|p| p.parse::<i64>().ok())
+                .ok_or_else(err)
+        };
+        let (year, month, day) = (next()?, next()? as u32, next()? as u32);
+        let date = Self {
+            year: year as i32,
+            month,
+            day,
+        };
+        // reject dates like 2023-02-30 by checking the round trip
+        if !(1..=12).contains(&month) || Self::from_days(date.days()) != date {
+            return Err(err());
+        }
+        Ok(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> Date {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn epoch() {
+        let epoch = Date::from_days(0);
+        assert_eq!(epoch.to_string(), "1970-01-01");
+        assert_eq!(epoch.days(), 0);
+        assert_eq!(epoch.weekday(), 3);
+        assert_eq!(Date::from_days(-1).to_string(), "1969-12-31");
+        assert_eq!(Date::from_timestamp(86399), epoch);
+    }
+
+    #[test]
+    fn days_round_trip() {
+        for days in -800_000..800_000 {
+            assert_eq!(Date::from_days(days).days(), days);
+        }
+    }
+
+    #[test]
+    fn leap_years() {
+        for leap in ["2000-02-29", "2024-02-29", "1972-02-29"] {
+            let day = date(leap);
+            assert_eq!(Date::from_days(day.days()), day);
+            assert_eq!(Date::from_days(day.days() + 1).month, 3);
+        }
+        for not_leap in ["1900-02-29", "2100-02-29", "2023-02-29"] {
+            assert!(not_leap.parse::<Date>().is_err());
+        }
+        assert_eq!(date("2024-03-01").days() - date("2024-02-28").days(), 2);
+        assert_eq!(date("2023-03-01").days() - date("2023-02-28").days(), 1);
+        assert_eq!(date("2001-01-01").days() - date("2000-01-01").days(), 366);
+    }
+
+    #[test]
+    fn invalid_dates() {
+        for s in ["2024-13-01", "2024-00-10", "2024-04-31", "2024-4", "today"] {
+            assert!(s.parse::<Date>().is_err(), "{s}");
+        }
+    }
+}
@@ -0,0 +1,418 @@
+use std::{num::ParseIntError, str::FromStr};
+
+use rand::{seq::SliceRandom, Rng};
+
+/// Which part of a text to practice.
+#[derive(Debug, Clone, Default)]
+pub enum Excerpt {
+    /// The whole text.
+    #[default]
+    Whole,
+    /// An inclusive, 1-based range of lines.
+    Lines(LineRange),
+    /// About this many lines at random, cut at blank lines and balanced braces.
+    RandomLines(usize),
+    /// About this many words at random, cut at sentence ends.
+    RandomWords(usize),
+    /// A random paragraph.
+    RandomParagraph,
+    /// The n-th paragraph, 1-based.
+    Paragraph(usize),
+    /// The definition of a function with the given name.
+    Function(String),
+}
+
+/// Line range in the form of `START:END`, where either side can be omitted.
+#[derive(Debug, Clone, Copy)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl FromStr for LineRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| n.parse().map_err(|e: ParseIntError| format!("{n:?}: {e}"));
+        let (start, end) = s.split_once(':').unwrap_or((s, s));
+        let start = if start.is_empty() { 1 } else { parse(start)? };
+        let end = if end.is_empty() {
+            None
+        } else {
+            Some(parse(end)?)
+        };
+        if end.is_some_and(|end| end < start) {
+            return Err(format!("the range ends before line {start} starts"));
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl Excerpt {
+    /// Cut the excerpt out of `text`, or `None` if there is nothing to cut.
+    pub fn cut<R: Rng>(&self, text: &str, rng: &mut R) -> Option<String> {
+        let lines: Vec<&str> = text.lines().collect();
+        let excerpt = match self {
+            Excerpt::Whole => text.to_string(),
+            Excerpt::Lines(range) => {
+                let start = range.start.max(1) - 1;
+                let end = range.end.unwrap_or(lines.len()).min(lines.len());
+                lines.get(start..end)?.join("\n")
+            }
+            Excerpt::RandomLines(n) => {
+                let (start, end) = random_lines(&lines, *n, rng)?;
+                lines[start..end].join("\n")
+            }
+            Excerpt::RandomWords(n) => random_words(text, *n, rng)?,
+            Excerpt::RandomParagraph => paragraphs(text).choose(rng)?.to_string(),
+            Excerpt::Paragraph(n) => paragraphs(text).get(n.checked_sub(1)?)?.to_string(),
+            Excerpt::Function(name) => {
+                let (start, end) = function(&lines, name)?;
+                lines[start..end].join("\n")
+            }
+        };
+        if excerpt.trim().is_empty() {
+            None
+        } else {
+            Some(excerpt.trim_end().to_string())
+        }
+    }
+}
+
+/// Split text into paragraphs separated by blank lines.
+#[must_use]
+pub fn paragraphs(text: &str) -> Vec<&str> {
+    let mut paragraphs = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(s) = start.take() {
+                paragraphs.push(text[s..end].trim_end());
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + line.len();
+        }
+        offset += line.len();
+    }
+    if let Some(s) = start {
+        paragraphs.push(text[s..end].trim_end());
+    }
+    paragraphs
+}
+
+/// Change of brace depth over a line.
+fn brace_delta(line: &str) -> i32 {
+    line.chars()
+        .map(|c| match c {
+            '{' => 1,
+            '}' => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+#[inline]
+fn is_blank(lines: &[&str], i: usize) -> bool {
+    lines.get(i).is_none_or(|line| line.trim().is_empty())
+}
+
+/// Pick about `n` lines, starting after a blank line and ending before one,
+/// without leaving a brace block half open.
+fn random_lines<R: Rng>(lines: &[&str], n: usize, rng: &mut R) -> Option<(usize, usize)> {
+    let mut starts: Vec<usize> = (0..lines.len())
+        .filter(|&i| !is_blank(lines, i) && (i == 0 || is_blank(lines, i - 1)))
+        .collect();
+    starts.shuffle(rng);
+    // a start within an unbalanced block has no good end, so try the next one
+    starts
+        .into_iter()
+        .find_map(|start| Some((start, balanced_end(lines, start, n)?)))
+}
+
+/// End of about `n` lines from `start` where all braces opened on the way are closed again.
+fn balanced_end(lines: &[&str], start: usize, n: usize) -> Option<usize> {
+    let limit = start + n.max(1) * 3;
+
+    let mut depth = 0;
+    let mut last_end = None;
+    let mut fallback = None;
+    for i in start..lines.len() {
+        depth += brace_delta(lines[i]);
+        if depth < 0 {
+            // closing a block that started before the excerpt
+            break;
+        }
+        if depth > 0 {
+            // a block may go on past the limit, but has to be closed
+            continue;
+        }
+        if is_blank(lines, i + 1) {
+            last_end = Some(i + 1);
+            if i + 1 - start >= n {
+                break;
+            }
+        }
+        if i + 1 - start >= n {
+            fallback.get_or_insert(i + 1);
+        }
+        if i + 1 >= limit {
+            break;
+        }
+    }
+    last_end.or(fallback)
+}
+
+/// Pick about `n` words, starting and ending at a sentence boundary.
+fn random_words<R: Rng>(text: &str, n: usize, rng: &mut R) -> Option<String> {
+    let ends_sentence = |word: &str| {
+        word.trim_end_matches(['"', '\'', ')'])
+            .ends_with(['.', '!', '?'])
+    };
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let starts: Vec<usize> = (0..words.len())
+        .filter(|&i| i == 0 || ends_sentence(words[i - 1]))
+        .collect();
+    let start = *starts.choose(rng)?;
+    let limit = (start + n.max(1) * 2).min(words.len());
+
+    let mut end = (start + n).min(words.len());
+    if let Some(i) = (end.max(start + 1) - 1..limit).find(|&i| ends_sentence(words[i])) {
+        end = i + 1;
+    }
+    Some(words[start..end].join(" "))
+}
+
+/// Keywords right before the name of a function being defined, in the usual languages.
+const DEFINITION_KEYWORDS: &[&str] = &[
+    "fn", "def", "function", "func", "fun", "sub", "proc", "defun", "defn",
+];
+
+/// Words that start a statement or an expression, so a name after them is called rather
+/// than defined.
+const NOT_DEFINING: &[&str] = &[
+    "if", "else", "while", "for", "do", "switch", "case", "match", "return", "yield", "await",
+    "new", "let", "var", "throw", "not", "and", "or", "in", "is", "print", "echo",
+];
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Positions of `name` in `line` as a whole identifier followed by `(` or `<`.
+fn name_positions<'a>(line: &'a str, name: &'a str) -> impl Iterator<Item = usize> + 'a {
+    line.match_indices(name).map(|(i, _)| i).filter(move |&i| {
+        let before = line[..i].chars().next_back();
+        let after = line[i + name.len()..].trim_start().chars().next();
+        !before.is_some_and(is_ident) && matches!(after, Some('(' | '<'))
+    })
+}
+
+/// Text after the parameter list starting in `rest`, unless it goes on past the line.
+fn after_params(rest: &str) -> Option<&str> {
+    let open = rest.find('(')?;
+    let mut depth = 0;
+    for (i, c) in rest[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => (),
+        }
+        if depth == 0 {
+            return Some(rest[open + i + 1..].trim());
+        }
+    }
+    None
+}
+
+/// Whether `line` defines function `name` with a keyword like `fn` or `def`, skipping
+/// declarations without a body such as trait methods.
+fn defines_with_keyword(line: &str, name: &str) -> bool {
+    name_positions(line, name).any(|i| {
+        let keyword = line[..i]
+            .split_whitespace()
+            .next_back()
+            .map(|word| word.trim_matches(|c: char| !is_ident(c)))
+            .is_some_and(|word| DEFINITION_KEYWORDS.contains(&word));
+        let declaration =
+            after_params(&line[i + name.len()..]).is_some_and(|rest| rest.ends_with(';'));
+        keyword && !declaration
+    })
+}
+
+/// Whether `line` starts with function `name`, after modifiers and a return type if any, and
+/// goes on with a block rather than ending like a call, as in C or in JavaScript classes.
+fn defines_without_keyword(line: &str, name: &str) -> bool {
+    name_positions(line, name).any(|i| {
+        let before = &line[..i];
+        // `Foo::name(` and `x.name(` are calls, `int *name(` is not
+        let unqualified = before
+            .chars()
+            .next_back()
+            .is_none_or(|c| c.is_whitespace() || c == '*' || c == '&');
+        let leading_words = before.split_whitespace().all(|word| {
+            word.chars().all(|c| is_ident(c) || c == '*' || c == '&')
+                && !NOT_DEFINING.contains(&word)
+        });
+        let opens_block = match after_params(&line[i + name.len()..]) {
+            None => true,
+            Some(rest) => {
+                rest.is_empty()
+                    || ["{", ":", "->", "=>"].iter().any(|p| rest.starts_with(p))
+                    || rest.starts_with(|c: char| c.is_alphabetic())
+            }
+        };
+        unqualified && leading_words && opens_block
+    })
+}
+
+/// Find the definition of function `name`, along with the comments right above it.
+///
+/// Definitions with a keyword are looked for first, then lines that start with the name after
+/// modifiers and a return type.
+/// Blocks are delimited by balanced braces, or by indentation if the definition ends with `:`.
+fn function(lines: &[&str], name: &str) -> Option<(usize, usize)> {
+    let head = lines
+        .iter()
+        .position(|line| defines_with_keyword(line, name))
+        .or_else(|| {
+            lines
+                .iter()
+                .position(|line| defines_without_keyword(line, name))
+        })?;
+
+    // include doc comments and attributes
+    let mut start = head;
+    while start > 0 {
+        let prev = lines[start - 1].trim_start();
+        if ["//", "/*", "*", "#", "@"]
+            .iter()
+            .any(|p| prev.starts_with(p))
+        {
+            start -= 1;
+        } else {
+            break;
+        }
+    }
+
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let mut end = head;
+    let mut depth = 0;
+    let mut opened = false;
+    while end < lines.len() {
+        let line = lines[end];
+        if !opened && !line.contains('{') && line.trim_end().ends_with(':') {
+            // indentation based block, python style
+            let base = indent(lines[head]);
+            end += 1;
+            while end < lines.len() && (is_blank(lines, end) || indent(lines[end]) > base) {
+                end += 1;
+            }
+            while end > head + 1 && is_blank(lines, end - 1) {
+                end -= 1;
+            }
+            return Some((start, end));
+        }
+        depth += brace_delta(line);
+        opened |= line.contains('{');
+        end += 1;
+        if opened && depth <= 0 {
+            return Some((start, end));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    const CODE: &str = "use std::io;
+
+fn first() {
+    let a = 1;
+
+    if a > 0 {
+        println!(\"{a}\");
+    }
+}
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn second() -> i32 {
+    let b = 2;
+
+    b * 2
+}
+
+const LAST: i32 = 3;
+";
+
+    fn depths(excerpt: &str) -> Vec<i32> {
+        excerpt
+            .lines()
+            .scan(0, |depth, line| {
+                *depth += brace_delta(line);
+                Some(*depth)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn random_lines_are_balanced() {
+        for seed in 0..200 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            for n in [1, 2, 5, 10] {
+                let excerpt = Excerpt::RandomLines(n).cut(CODE, &mut rng).unwrap();
+                let depths = depths(&excerpt);
+                assert!(depths.iter().all(|&d| d >= 0), "{excerpt}");
+                assert_eq!(depths.last(), Some(&0), "{excerpt}");
+            }
+        }
+    }
+
+    #[test]
+    fn random_lines_skip_starts_inside_a_block() {
+        let text = "fn main() {\n    a();\n\n}\n";
+        for seed in 0..50 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let excerpt = Excerpt::RandomLines(1).cut(text, &mut rng);
+            assert_eq!(excerpt.as_deref(), Some(text.trim_end()));
+        }
+    }
+
+    #[test]
+    fn random_lines_of_an_unbalanced_text() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        assert_eq!(
+            Excerpt::RandomLines(3).cut("fn open() {\n    a();\n", &mut rng),
+            None
+        );
+    }
+
+    #[test]
+    fn line_ranges() {
+        let range: LineRange = "3:5".parse().unwrap();
+        assert_eq!((range.start, range.end), (3, Some(5)));
+        let range: LineRange = ":5".parse().unwrap();
+        assert_eq!((range.start, range.end), (1, Some(5)));
+        let range: LineRange = "4:".parse().unwrap();
+        assert_eq!((range.start, range.end), (4, None));
+        let range: LineRange = "7".parse().unwrap();
+        assert_eq!((range.start, range.end), (7, Some(7)));
+    }
+
+    #[test]
+    fn invalid_line_ranges() {
+        for s in ["5:3", "a:3", "3:b", "-1:2", "1:2:3"] {
+            assert!(s.parse::<LineRange>().is_err(), "{s}");
+        }
+    }
+}
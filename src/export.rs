@@ -0,0 +1,388 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::{date::Date, history::Record, keylog::Entry};
+
+/// Version of the export schema, bumped whenever tables or columns change meaning.
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("`{s}` is not one of csv or json")),
+        }
+    }
+}
+
+/// A table of the export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Table {
+    /// One row per finished session.
+    Sessions,
+    /// One row per expected key, aggregated over all sessions.
+    Keys,
+    /// One row per key stroke.
+    Keystrokes,
+}
+
+impl FromStr for Table {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sessions" => Ok(Table::Sessions),
+            "keys" => Ok(Table::Keys),
+            "keystrokes" => Ok(Table::Keystrokes),
+            _ => Err(format!("`{s}` is not one of sessions, keys or keystrokes")),
+        }
+    }
+}
+
+impl Table {
+    const ALL: [Table; 3] = [Table::Sessions, Table::Keys, Table::Keystrokes];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Table::Sessions => "sessions",
+            Table::Keys => "keys",
+            Table::Keystrokes => "keystrokes",
+        }
+    }
+}
+
+/// A cell of a table, typed so that JSON gets numbers and booleans right.
+#[derive(Debug, Clone)]
+enum Value {
+    Int(u64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    Null,
+}
+
+impl Value {
+    fn csv(&self) -> String {
+        match self {
+            Value::Int(n) => n.to_string(),
+            Value::Float(x) => format!("{x:.3}"),
+            Value::Bool(b) => b.to_string(),
+            Value::Null => String::new(),
+            Value::Str(s) if s.contains([',', '"', '\n', '\r']) => {
+                format!("\"{}\"", s.replace('"', "\"\""))
+            }
+            Value::Str(s) => s.clone(),
+        }
+    }
+
+    fn json(&self) -> String {
+        match self {
+            Value::Int(n) => n.to_string(),
+            Value::Float(x) if x.is_finite() => format!("{x:.3}"),
+            Value::Float(_) | Value::Null => "null".to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::Str(s) => json_string(s),
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Name of a key for the export, spelled out for keys that are not printable.
+fn key_name(c: char) -> String {
+    match c {
+        ' ' => "space".to_string(),
+        '\n' => "enter".to_string(),
+        '\t' => "tab".to_string(),
+        '\x08' => "backspace".to_string(),
+        c => c.to_string(),
+    }
+}
+
+fn millis(d: Duration) -> Value {
+    Value::Int(d.as_millis() as u64)
+}
+
+struct Rows {
+    columns: &'static [&'static str],
+    rows: Vec<Vec<Value>>,
+}
+
+fn sessions(records: &[&Record]) -> Rows {
+    Rows {
+        columns: &[
+            "id",
+            "date",
+            "time",
+            "source",
+            "seed",
+            "elapsed",
+            "wpm",
+            "accuracy",
+            "n_correct",
+            "n_wrong",
+            "best_streak",
+            "text",
+        ],
+        rows: records
+            .iter()
+            .map(|r| {
+                vec![
+                    Value::Int(r.id as u64),
+                    Value::Str(r.date().to_string()),
+                    Value::Int(r.time),
+                    Value::Str(r.source.clone()),
+                    r.seed.map_or(Value::Null, Value::Int),
+                    Value::Float(r.elapsed),
+                    Value::Float(r.wpm),
+                    Value::Float(r.accuracy),
+                    Value::Int(r.n_correct as u64),
+                    Value::Int(r.n_wrong as u64),
+                    Value::Int(r.best_streak as u64),
+                    Value::Str(r.text.clone()),
+                ]
+            })
+            .collect(),
+    }
+}
+
+/// Totals of a key over many key strokes.
+#[derive(Default)]
+struct KeyTotals {
+    count: u64,
+    errors: u64,
+    interval: Duration,
+    n_intervals: u32,
+    dwell: Duration,
+    n_dwells: u32,
+    flight: Duration,
+    n_flights: u32,
+}
+
+fn keys(entries: &[&Entry]) -> Rows {
+    let mut totals: BTreeMap<char, KeyTotals> = BTreeMap::new();
+    let mut previous: Option<&Entry> = None;
+    for &entry in entries {
+        let Some(expected) = entry.expected else {
+            previous = Some(entry);
+            continue;
+        };
+        let key = totals.entry(expected).or_default();
+        key.count += 1;
+        if entry.correct == Some(false) {
+            key.errors += 1;
+        } else if let Some(previous) = previous.filter(|p| p.session == entry.session) {
+            key.interval += entry.time.saturating_sub(previous.time);
+            key.n_intervals += 1;
+        }
+        if let Some(dwell) = entry.dwell {
+            key.dwell += dwell;
+            key.n_dwells += 1;
+        }
+        if let Some(flight) = entry.flight {
+            key.flight += flight;
+            key.n_flights += 1;
+        }
+        previous = Some(entry);
+    }
+    Rows {
+        columns: &[
+            "key",
+            "count",
+            "errors",
+            "error_rate",
+            "mean_interval_ms",
+            "mean_dwell_ms",
+            "mean_flight_ms",
+        ],
+        rows: totals
+            .into_iter()
+            .map(|(c, key)| {
+                vec![
+                    Value::Str(key_name(c)),
+                    Value::Int(key.count),
+                    Value::Int(key.errors),
+                    Value::Float(key.errors as f64 * 100.0 / key.count as f64),
+                    if key.n_intervals > 0 {
+                        millis(key.interval / key.n_intervals)
+                    } else {
+                        Value::Null
+                    },
+                    if key.n_dwells > 0 {
+                        millis(key.dwell / key.n_dwells)
+                    } else {
+                        Value::Null
+                    },
+                    if key.n_flights > 0 {
+                        millis(key.flight / key.n_flights)
+                    } else {
+                        Value::Null
+                    },
+                ]
+            })
+            .collect(),
+    }
+}
+
+fn keystrokes(entries: &[&Entry]) -> Rows {
+    Rows {
+        columns: &[
+            "session",
+            "index",
+            "key",
+            "expected",
+            "correct",
+            "time_ms",
+            "dwell_ms",
+            "flight_ms",
+        ],
+        rows: entries
+            .iter()
+            .map(|e| {
+                vec![
+                    Value::Int(e.session as u64),
+                    Value::Int(e.index as u64),
+                    Value::Str(key_name(e.key)),
+                    e.expected.map_or(Value::Null, |c| Value::Str(key_name(c))),
+                    e.correct.map_or(Value::Null, Value::Bool),
+                    millis(e.time),
+                    e.dwell.map_or(Value::Null, millis),
+                    e.flight.map_or(Value::Null, millis),
+                ]
+            })
+            .collect(),
+    }
+}
+
+/// A CSV table, with the schema version in a last column as CSV has no place for it otherwise.
+fn csv(rows: &Rows) -> String {
+    let mut out = rows.columns.join(",");
+    out.push_str(",version\n");
+    for row in &rows.rows {
+        let cells: Vec<String> = row.iter().map(Value::csv).collect();
+        out.push_str(&cells.join(","));
+        out.push_str(&format!(",{VERSION}\n"));
+    }
+    out
+}
+
+fn json(rows: &Rows) -> String {
+    let objects: Vec<String> = rows
+        .rows
+        .iter()
+        .map(|row| {
+            let fields: Vec<String> = rows
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| format!("{}: {}", json_string(column), value.json()))
+                .collect();
+            format!("    {{{}}}", fields.join(", "))
+        })
+        .collect();
+    if objects.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n  ]", objects.join(",\n"))
+    }
+}
+
+/// Export the sessions since a date, with their key strokes.
+///
+/// CSV holds a single table, the sessions unless another one is asked for, with the schema
+/// version in every row. JSON holds all tables in an object along with the schema version,
+/// or only the one asked for.
+#[must_use]
+pub fn export(
+    records: &[Record],
+    entries: &[Entry],
+    format: Format,
+    table: Option<Table>,
+    since: Option<Date>,
+) -> String {
+    let records: Vec<&Record> = records
+        .iter()
+        .filter(|r| since.is_none_or(|since| r.date() >= since))
+        .collect();
+    let ids: HashSet<usize> = records.iter().map(|r| r.id).collect();
+    let entries: Vec<&Entry> = entries
+        .iter()
+        .filter(|e| ids.contains(&e.session))
+        .collect();
+    let rows = |table: Table| match table {
+        Table::Sessions => sessions(&records),
+        Table::Keys => keys(&entries),
+        Table::Keystrokes => keystrokes(&entries),
+    };
+
+    match format {
+        Format::Csv => csv(&rows(table.unwrap_or(Table::Sessions))),
+        Format::Json => {
+            let tables: Vec<String> = table
+                .map_or(Table::ALL.to_vec(), |table| vec![table])
+                .into_iter()
+                .map(|table| format!("  {}: {}", json_string(table.name()), json(&rows(table))))
+                .collect();
+            format!(
+                "{{\n  \"schema\": \"pecker-export\",\n  \"version\": {VERSION},\n{}\n}}\n",
+                tables.join(",\n")
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn csv_cell(s: &str) -> String {
+        Value::Str(s.to_string()).csv()
+    }
+
+    #[test]
+    fn csv_quotes_only_when_needed() {
+        assert_eq!(csv_cell("plain text"), "plain text");
+        assert_eq!(csv_cell("a,b"), "\"a,b\"");
+        assert_eq!(csv_cell("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_cell("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_cell("\","), "\"\"\",\"");
+    }
+
+    #[test]
+    fn csv_rows_carry_the_version() {
+        let rows = Rows {
+            columns: &["key", "text"],
+            rows: vec![vec![Value::Str(",".to_string()), Value::Null]],
+        };
+        assert_eq!(csv(&rows), format!("key,text,version\n\",\",,{VERSION}\n"));
+    }
+}
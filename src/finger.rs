@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::{layout::Layout, stat::Stat};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// A finger typing keys, in order from the left pinky to the right pinky.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    LeftThumb,
+    RightThumb,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+}
+
+impl Finger {
+    pub const ALL: [Finger; 10] = [
+        Finger::LeftPinky,
+        Finger::LeftRing,
+        Finger::LeftMiddle,
+        Finger::LeftIndex,
+        Finger::LeftThumb,
+        Finger::RightThumb,
+        Finger::RightIndex,
+        Finger::RightMiddle,
+        Finger::RightRing,
+        Finger::RightPinky,
+    ];
+
+    /// Fingers of a hand from the index outwards, as they are drawn.
+    #[must_use]
+    pub fn of_hand(hand: Hand) -> [Finger; 4] {
+        match hand {
+            Hand::Left => [
+                Finger::LeftIndex,
+                Finger::LeftMiddle,
+                Finger::LeftRing,
+                Finger::LeftPinky,
+            ],
+            Hand::Right => [
+                Finger::RightIndex,
+                Finger::RightMiddle,
+                Finger::RightRing,
+                Finger::RightPinky,
+            ],
+        }
+    }
+
+    /// The finger for a key at `col` on a row staggered keyboard: the index finger covers
+    /// the two columns next to the middle, the pinky everything from its column outwards.
+    #[must_use]
+    pub fn from_column(col: i16) -> Self {
+        match col {
+            ..=-5 => Finger::LeftPinky,
+            -4 => Finger::LeftRing,
+            -3 => Finger::LeftMiddle,
+            -2..=-1 => Finger::LeftIndex,
+            0 => Finger::RightThumb,
+            1..=2 => Finger::RightIndex,
+            3 => Finger::RightMiddle,
+            4 => Finger::RightRing,
+            5.. => Finger::RightPinky,
+        }
+    }
+
+    /// Column of the home key of the finger, 0 for the thumbs.
+    #[must_use]
+    pub fn column(self) -> i16 {
+        match self {
+            Finger::LeftPinky => -5,
+            Finger::LeftRing => -4,
+            Finger::LeftMiddle => -3,
+            Finger::LeftIndex => -2,
+            Finger::LeftThumb | Finger::RightThumb => 0,
+            Finger::RightIndex => 2,
+            Finger::RightMiddle => 3,
+            Finger::RightRing => 4,
+            Finger::RightPinky => 5,
+        }
+    }
+
+    #[must_use]
+    pub fn hand(self) -> Hand {
+        if self <= Finger::LeftThumb {
+            Hand::Left
+        } else {
+            Hand::Right
+        }
+    }
+
+    #[must_use]
+    pub fn is_thumb(self) -> bool {
+        matches!(self, Finger::LeftThumb | Finger::RightThumb)
+    }
+
+    /// The pinky of the other hand, which holds Shift while this finger types.
+    #[must_use]
+    pub fn shift_finger(self) -> Finger {
+        match self.hand() {
+            Hand::Left => Finger::RightPinky,
+            Hand::Right => Finger::LeftPinky,
+        }
+    }
+
+    /// Name of the finger without its hand.
+    #[must_use]
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Finger::LeftPinky | Finger::RightPinky => "pinky",
+            Finger::LeftRing | Finger::RightRing => "ring",
+            Finger::LeftMiddle | Finger::RightMiddle => "middle",
+            Finger::LeftIndex | Finger::RightIndex => "index",
+            Finger::LeftThumb | Finger::RightThumb => "thumb",
+        }
+    }
+
+    #[must_use]
+    pub fn name(self) -> String {
+        let hand = match self.hand() {
+            Hand::Left => "left",
+            Hand::Right => "right",
+        };
+        format!("{hand} {}", self.short_name())
+    }
+}
+
+/// How a finger did over a session.
+#[derive(Debug, Clone, Default)]
+pub struct FingerStat {
+    pub n_hit: usize,
+    pub n_miss: usize,
+    /// Sum of the times since the previous correct char, for the correct chars.
+    pub total_time: Duration,
+}
+
+impl FingerStat {
+    /// Average time per correct char.
+    #[must_use]
+    pub fn average(&self) -> Option<Duration> {
+        (self.n_hit > 0).then(|| self.total_time / self.n_hit as u32)
+    }
+
+    /// Ratio of wrong chars, in percent.
+    #[must_use]
+    pub fn error_rate(&self) -> f64 {
+        let total = self.n_hit + self.n_miss;
+        if total == 0 {
+            0.0
+        } else {
+            self.n_miss as f64 * 100.0 / total as f64
+        }
+    }
+}
+
+/// Speed and errors of each finger typing `text`, from the hits and misses of a session.
+#[must_use]
+pub fn finger_stats(text: &str, stat: &Stat, layout: &Layout) -> BTreeMap<Finger, FingerStat> {
+    let text: Vec<char> = text.chars().collect();
+    let finger_at = |position: usize| text.get(position).and_then(|&c| layout.finger(c));
+    let mut stats: BTreeMap<Finger, FingerStat> = BTreeMap::new();
+    // the first char only starts the clock
+    for pair in stat.hits.windows(2) {
+        let ((_, previous), (position, time)) = (pair[0], pair[1]);
+        if let Some(finger) = finger_at(position) {
+            let finger_stat = stats.entry(finger).or_default();
+            finger_stat.n_hit += 1;
+            finger_stat.total_time += time.saturating_sub(previous);
+        }
+    }
+    for &(position, _) in &stat.misses {
+        if let Some(finger) = finger_at(position) {
+            stats.entry(finger).or_default().n_miss += 1;
+        }
+    }
+    stats
+}
+
+/// Lines for the results screen, one per hand.
+#[must_use]
+pub fn summary(stats: &BTreeMap<Finger, FingerStat>) -> Vec<String> {
+    [(Hand::Left, "left "), (Hand::Right, "right")]
+        .into_iter()
+        .map(|(hand, name)| {
+            let mut fingers = Finger::of_hand(hand);
+            // from left to right
+            if hand == Hand::Left {
+                fingers.reverse();
+            }
+            let cells: Vec<String> = fingers
+                .into_iter()
+                .filter_map(|finger| {
+                    let finger_stat = stats.get(&finger)?;
+                    Some(format!(
+                        "{} {}ms {:.0}%",
+                        finger.short_name(),
+                        finger_stat.average()?.as_millis(),
+                        finger_stat.error_rate()
+                    ))
+                })
+                .collect();
+            format!("{name}  {}", cells.join(" | "))
+        })
+        .collect()
+}
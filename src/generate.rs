@@ -0,0 +1,216 @@
+use std::io::{Error, ErrorKind, Result};
+
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    date::Date,
+    lesson::Lesson,
+    ngram,
+    quote::{self, Length},
+    review::Deck,
+    session::PracticeText,
+    source::TextSource,
+    wordlist::WordList,
+};
+
+/// Where practice texts come from.
+pub enum Generator {
+    /// Random words from a word list.
+    Words {
+        words: WordList,
+        n_words: usize,
+        decoration: Decoration,
+    },
+    /// Files, directories or stdin.
+    Source(TextSource),
+    /// Quotes from the bundled corpus, of the given length class if any.
+    Quotes(Option<Length>),
+    /// Drills of a touch typing lesson.
+    Lesson {
+        lesson: Lesson,
+        words: WordList,
+        n_words: usize,
+    },
+    /// Drills of bigrams and trigrams.
+    Ngrams {
+        targets: Vec<String>,
+        words: WordList,
+        n_words: usize,
+    },
+    /// Problem words due for review, mixed with new words.
+    Review { words: WordList, n_words: usize },
+    /// The very same text over and over, e.g. from the history.
+    Fixed(PracticeText),
+}
+
+impl Generator {
+    /// Generate a text, which is always the same for the same seed.
+    ///
+    /// The seed is kept with the text, unless the text depends on more than the seed.
+    pub fn generate(&self, seed: u64) -> Result<PracticeText> {
+        // unlike `StdRng`, the algorithm is fixed, so seeds give the same texts in every version
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut practice = match self {
+            Generator::Words {
+                words,
+                n_words,
+                decoration,
+            } => PracticeText {
+                text: random_text(words, *n_words, decoration, &mut rng),
+                align_center: true,
+                source: format!("words:{}", words.name),
+                seed: None,
+            },
+            Generator::Source(source) => source.pick(&mut rng)?,
+            Generator::Quotes(length) => quote::pick(*length, &mut rng)
+                .ok_or_else(|| Error::new(ErrorKind::NotFound, "no quote of this length"))?
+                .practice(),
+            Generator::Lesson {
+                lesson,
+                words,
+                n_words,
+            } => PracticeText {
+                text: lesson.drill(words, *n_words, &mut rng),
+                align_center: true,
+                source: format!("lesson {}: {}", lesson.id, lesson.name),
+                seed: None,
+            },
+            Generator::Ngrams {
+                targets,
+                words,
+                n_words,
+            } => PracticeText {
+                text: ngram::drill(targets, words, *n_words, &mut rng),
+                align_center: true,
+                source: format!("ngrams:{}", words.name),
+                seed: None,
+            },
+            // the words due change with every session, so the seed alone cannot repeat it
+            Generator::Review { words, n_words } => {
+                return Ok(PracticeText {
+                    text: Deck::load()?.drill(words, *n_words, Date::today().days(), &mut rng),
+                    align_center: true,
+                    source: format!("review:{}", words.name),
+                    seed: None,
+                })
+            }
+            Generator::Fixed(practice) => return Ok(practice.clone()),
+        };
+        practice.seed = Some(seed);
+        Ok(practice)
+    }
+}
+
+/// Sequence of seeds for the texts of a run, starting from a base seed.
+pub struct Seeds {
+    next: u64,
+    rng: ChaCha8Rng,
+}
+
+impl Seeds {
+    #[must_use]
+    pub fn new(base: u64) -> Self {
+        Self {
+            next: base,
+            rng: ChaCha8Rng::seed_from_u64(base),
+        }
+    }
+
+    /// Seeds of the daily challenge, the same for everyone on the same day (UTC).
+    #[must_use]
+    pub fn daily(date: Date) -> Self {
+        Self::new((date.days() as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+    }
+
+    pub fn next_seed(&mut self) -> u64 {
+        std::mem::replace(&mut self.next, self.rng.gen())
+    }
+}
+
+/// Probabilities of decorating each word of a random text, so that Shift, digits
+/// and punctuation get practiced as well.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Decoration {
+    /// Capitalize the first letter.
+    pub capitalize: f64,
+    /// Append a comma, period or other punctuation mark.
+    pub punctuation: f64,
+    /// Wrap in quotes or brackets.
+    pub brackets: f64,
+    /// Put a number in front.
+    pub numbers: f64,
+}
+
+const PUNCTUATION: &[(char, u32)] = &[(',', 6), ('.', 6), (';', 1), (':', 1), ('!', 1), ('?', 1)];
+
+const BRACKETS: &[(char, char)] = &[
+    ('"', '"'),
+    ('\'', '\''),
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('<', '>'),
+];
+
+/// Build a text of `n` random words from `words`.
+pub fn random_text<R: Rng>(
+    words: &WordList,
+    n: usize,
+    decoration: &Decoration,
+    rng: &mut R,
+) -> String {
+    let mut text = Vec::new();
+    let mut sentence_start = false;
+    for word in words.sample(rng, n) {
+        if rng.gen_bool(decoration.numbers) {
+            text.push(random_number(rng));
+        }
+
+        let mut word = word.to_string();
+        if sentence_start || rng.gen_bool(decoration.capitalize) {
+            word = capitalize(&word);
+        }
+        if rng.gen_bool(decoration.brackets) {
+            let (open, close) = BRACKETS.choose(rng).copied().unwrap_or(('"', '"'));
+            word = format!("{open}{word}{close}");
+        }
+        sentence_start = false;
+        if rng.gen_bool(decoration.punctuation) {
+            let (mark, _) = PUNCTUATION
+                .choose_weighted(rng, |(_, weight)| *weight)
+                .copied()
+                .unwrap_or(('.', 1));
+            sentence_start = matches!(mark, '.' | '!' | '?');
+            word.push(mark);
+        }
+        text.push(word);
+    }
+    text.join(" ")
+}
+
+/// A drill repeating each of `words` a few times, in shuffled order.
+pub fn repeat_words<R: Rng>(words: &[String], times: usize, rng: &mut R) -> String {
+    let mut text: Vec<&str> = words
+        .iter()
+        .flat_map(|word| std::iter::repeat_n(word.as_str(), times))
+        .collect();
+    text.shuffle(rng);
+    text.join(" ")
+}
+
+/// Upper case the first letter of a word.
+#[must_use]
+pub fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// A number of one to four digits, more often short ones.
+fn random_number<R: Rng>(rng: &mut R) -> String {
+    let digits = [1, 1, 2, 2, 2, 3, 4].choose(rng).copied().unwrap_or(2);
+    rng.gen_range(0..10u32.pow(digits)).to_string()
+}
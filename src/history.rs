@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::io::Result;
+
+use crate::{date::Date, stat::format_duration, storage::Table};
+
+/// Results of a finished session, as kept in the history file.
+#[derive(Debug, Clone, Default)]
+pub struct Record {
+    /// Position in the history, starting from 1.
+    pub id: usize,
+    /// Seconds since the unix epoch when the session ended.
+    pub time: u64,
+    /// Where the text came from, e.g. `words:english` or a file path.
+    pub source: String,
+    /// Seed the text was generated with, if it was generated.
+    pub seed: Option<u64>,
+    pub text: String,
+    pub align_center: bool,
+    /// Typing time in seconds, without pauses.
+    pub elapsed: f64,
+    pub wpm: f64,
+    pub accuracy: f64,
+    pub n_correct: usize,
+    pub n_wrong: usize,
+    pub best_streak: usize,
+}
+
+/// The history file, its columns are looked up by name so older versions only lack the
+/// newer ones.
+const TABLE: Table = Table {
+    file: "history.tsv",
+    kind: "history",
+    version: 1,
+    columns: &[
+        "time",
+        "source",
+        "seed",
+        "elapsed",
+        "wpm",
+        "accuracy",
+        "n_correct",
+        "n_wrong",
+        "best_streak",
+        "align_center",
+        "text",
+    ],
+};
+
+impl Record {
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.time.to_string(),
+            self.source.clone(),
+            self.seed.map(|s| s.to_string()).unwrap_or_default(),
+            format!("{:.3}", self.elapsed),
+            format!("{:.2}", self.wpm),
+            format!("{:.2}", self.accuracy),
+            self.n_correct.to_string(),
+            self.n_wrong.to_string(),
+            self.best_streak.to_string(),
+            self.align_center.to_string(),
+            self.text.clone(),
+        ]
+    }
+
+    fn from_fields(id: usize, fields: &HashMap<&str, String>) -> Self {
+        fn get<T: std::str::FromStr + Default>(fields: &HashMap<&str, String>, key: &str) -> T {
+            fields
+                .get(key)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default()
+        }
+        Self {
+            id,
+            time: get(fields, "time"),
+            source: get(fields, "source"),
+            seed: fields.get("seed").and_then(|v| v.parse().ok()),
+            text: get(fields, "text"),
+            align_center: get(fields, "align_center"),
+            elapsed: get(fields, "elapsed"),
+            wpm: get(fields, "wpm"),
+            accuracy: get(fields, "accuracy"),
+            n_correct: get(fields, "n_correct"),
+            n_wrong: get(fields, "n_wrong"),
+            best_streak: get(fields, "best_streak"),
+        }
+    }
+
+    #[must_use]
+    pub fn date(&self) -> Date {
+        Date::from_timestamp(self.time)
+    }
+
+    /// Lines for the results screen.
+    #[must_use]
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("{:.0} wpm", self.wpm),
+            format!(
+                "{:.1}% accuracy | {} | best streak {}",
+                self.accuracy,
+                format_duration(std::time::Duration::from_secs_f64(self.elapsed)),
+                self.best_streak,
+            ),
+            String::new(),
+            format!("source: {}", self.source),
+        ];
+        if let Some(seed) = self.seed {
+            lines.push(format!("seed: {seed}"));
+        }
+        if self.id != 0 {
+            lines.push(format!(
+                "session #{} (replay with --replay {})",
+                self.id, self.id
+            ));
+        }
+        lines
+    }
+}
+
+/// All finished sessions, oldest first.
+#[derive(Default)]
+pub struct History {
+    pub records: Vec<Record>,
+}
+
+impl History {
+    /// Load the history file.
+    pub fn load() -> Result<Self> {
+        let file = TABLE.load()?;
+        let records = file
+            .rows
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let fields = file.columns.iter().map(String::as_str).zip(row).collect();
+                Record::from_fields(i + 1, &fields)
+            })
+            .collect();
+        Ok(Self { records })
+    }
+
+    /// Write the whole history file, in the current format.
+    pub fn save(&self) -> Result<()> {
+        let rows: Vec<Vec<String>> = self.records.iter().map(Record::fields).collect();
+        TABLE.save(&rows)
+    }
+
+    /// Add a record to the history file, returning it with its id assigned.
+    pub fn append(record: &Record) -> Result<Record> {
+        let mut history = Self::load()?;
+        let record = Record {
+            id: history.records.len() + 1,
+            ..record.clone()
+        };
+        history.records.push(record.clone());
+        history.save()?;
+        Ok(record)
+    }
+
+    #[must_use]
+    pub fn get(&self, id: usize) -> Option<&Record> {
+        self.records.get(id.checked_sub(1)?)
+    }
+}
@@ -0,0 +1,129 @@
+use std::io::Result;
+use std::time::Duration;
+
+use crate::{stat::Stat, storage::Table};
+
+/// The keystroke log, which unlike the history is only ever appended to.
+const TABLE: Table = Table {
+    file: "keystrokes.tsv",
+    kind: "keystrokes",
+    version: 1,
+    columns: &[
+        "session",
+        "index",
+        "key",
+        "expected",
+        "correct",
+        "time_ms",
+        "dwell_ms",
+        "flight_ms",
+    ],
+};
+
+/// A key stroke of a finished session, as kept in the keystroke log.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// Id of the session in the history.
+    pub session: usize,
+    /// Position of the key stroke in the session, starting from 0.
+    pub index: usize,
+    pub key: char,
+    /// Char that was expected, unless the key was a backspace.
+    pub expected: Option<char>,
+    pub correct: Option<bool>,
+    /// Time since the first key stroke of the session.
+    pub time: Duration,
+    /// How long the key was held down, if the terminal reports releases.
+    pub dwell: Option<Duration>,
+    /// Time since the previous key was released, unless it was still held down or the
+    /// terminal does not report releases.
+    pub flight: Option<Duration>,
+}
+
+impl Entry {
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.session.to_string(),
+            self.index.to_string(),
+            self.key.to_string(),
+            self.expected.map(String::from).unwrap_or_default(),
+            self.correct.map(|c| c.to_string()).unwrap_or_default(),
+            self.time.as_millis().to_string(),
+            self.dwell
+                .map(|d| d.as_millis().to_string())
+                .unwrap_or_default(),
+            self.flight
+                .map(|d| d.as_millis().to_string())
+                .unwrap_or_default(),
+        ]
+    }
+
+    fn from_fields(fields: &[String]) -> Option<Self> {
+        // the flight time was added later, without changing the other columns
+        let (fields, flight) = match fields {
+            [fields @ .., flight] if fields.len() == TABLE.columns.len() - 1 => {
+                (fields, Some(flight))
+            }
+            fields => (fields, None),
+        };
+        let [session, index, key, expected, correct, time, dwell] = fields else {
+            return None;
+        };
+        Some(Self {
+            session: session.parse().ok()?,
+            index: index.parse().ok()?,
+            key: key.chars().next()?,
+            expected: expected.chars().next(),
+            correct: correct.parse().ok(),
+            time: Duration::from_millis(time.parse().ok()?),
+            dwell: dwell.parse().ok().map(Duration::from_millis),
+            flight: flight
+                .and_then(|f| f.parse().ok())
+                .map(Duration::from_millis),
+        })
+    }
+}
+
+/// Log entries for the key strokes of a session over `text`.
+#[must_use]
+pub fn entries(session: usize, text: &str, stat: &Stat) -> Vec<Entry> {
+    let text: Vec<char> = text.chars().collect();
+    let Some(first) = stat.keystrokes.first().map(|s| s.pressed) else {
+        return Vec::new();
+    };
+    stat.keystrokes
+        .iter()
+        .enumerate()
+        .map(|(index, stroke)| Entry {
+            session,
+            index,
+            key: stroke.key,
+            expected: stroke.position.and_then(|p| text.get(p).copied()),
+            correct: stroke.hit,
+            time: stroke.pressed.duration_since(first),
+            dwell: stroke
+                .released
+                .and_then(|r| r.checked_duration_since(stroke.pressed)),
+            flight: index
+                .checked_sub(1)
+                .and_then(|i| stat.keystrokes[i].released)
+                .and_then(|r| stroke.pressed.checked_duration_since(r)),
+        })
+        .collect()
+}
+
+/// Add entries to the keystroke log.
+pub fn append(entries: &[Entry]) -> Result<()> {
+    let rows: Vec<Vec<String>> = entries.iter().map(Entry::fields).collect();
+    TABLE.append(&rows)
+}
+
+/// All entries of the keystroke log, oldest first.
+pub fn load() -> Result<Vec<Entry>> {
+    Ok(TABLE
+        .load()?
+        .rows
+        .iter()
+        .filter_map(|row| Entry::from_fields(row))
+        .collect())
+}
@@ -1,24 +1,101 @@
-use std::{cmp::Ordering, collections::HashMap, io::Result};
+use std::{cmp::Ordering, collections::HashMap, io::Result, str::FromStr, time::Duration};
 
 use crossterm::style::Stylize;
 
-use crate::{screen::MainScreen, text::Expect};
+use crate::{
+    finger::{Finger, Hand},
+    screen::MainScreen,
+    text::Expect,
+};
 
 #[derive(Default)]
 pub struct Layout {
     keyboard_pos: HashMap<char, (i16, i16, bool)>,
+    /// Finger typing each key.
+    fingers: HashMap<char, Finger>,
 }
 
+/// When the keyboard with the finger to use is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Hints {
+    #[default]
+    Always,
+    Never,
+    /// From a miss until the next correct char.
+    AfterMiss,
+    /// Once no key has been pressed for a while.
+    Hesitation(Duration),
+}
+
+/// Pause before the keyboard is shown, unless another one is given.
+const HESITATION: Duration = Duration::from_millis(1000);
+
+impl FromStr for Hints {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "always" => Ok(Hints::Always),
+            None if s == "never" => Ok(Hints::Never),
+            None if s == "after-miss" => Ok(Hints::AfterMiss),
+            None if s == "hesitation" => Ok(Hints::Hesitation(HESITATION)),
+            Some(("hesitation", ms)) => ms
+                .parse()
+                .map(|ms| Hints::Hesitation(Duration::from_millis(ms)))
+                .map_err(|_| format!("`{ms}` is not a number of milliseconds")),
+            _ => Err(format!(
+                "`{s}` is not one of always, never, after-miss or hesitation[:MS]"
+            )),
+        }
+    }
+}
+
+/// Keys of a layout, row by row from the number row down, each as the unshifted and
+/// shifted keys of the left hand from the middle outwards, then the same for the right hand.
+type Rows = [[&'static str; 4]; 4];
+
+const QWERTY: Rows = [
+    ["54321`", "%$#@!~", "67890-=\x08", "^&*()_+"],
+    ["trewq\t", "TREWQ", "yuiop[]\\", "YUIOP{}|"],
+    ["gfdsa", "GFDSA", "hjkl;'\n", "HJKL:\""],
+    ["bvcxz", "BVCXZ", "nm,./", "NM<>?"],
+];
+
+const DVORAK: Rows = [
+    ["54321`", "%$#@!~", "67890[]\x08", "^&*(){}"],
+    ["yp.,'\t", "YP><\"", "fgcrl/=\\", "FGCRL?+|"],
+    ["iueoa", "IUEOA", "dhtns-\n", "DHTNS_"],
+    ["xkjq;", "XKJQ:", "bmwvz", "BMWVZ"],
+];
+
+const COLEMAK: Rows = [
+    ["54321`", "%$#@!~", "67890-=\x08", "^&*()_+"],
+    ["gpfwq\t", "GPFWQ", "jluy;[]\\", "JLUY:{}|"],
+    ["dtsra", "DTSRA", "hneio'\n", "HNEIO\""],
+    ["bvcxz", "BVCXZ", "km,./", "KM<>?"],
+];
+
+/// Built-in layouts by name, the first one is the default.
+pub const LAYOUTS: &[(&str, Rows)] =
+    &[("qwerty", QWERTY), ("dvorak", DVORAK), ("colemak", COLEMAK)];
+
 impl Layout {
     #[must_use]
     pub fn new() -> Self {
+        Self::from_rows(&QWERTY)
+    }
+
+    /// A built-in layout by name.
+    #[must_use]
+    pub fn named(name: &str) -> Option<Self> {
+        LAYOUTS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, rows)| Self::from_rows(rows))
+    }
+
+    fn from_rows(layout_str: &Rows) -> Self {
         let mut layout = Self::default();
-        let layout_str = vec![
-            ["54321`", "%$#@!~", "67890-=\x08", "^&*()_+"],
-            ["trewq\t", "TREWQ", "yuiop[]\\", "YUIOP{}|"],
-            ["gfdsa", "GFDSA", "hjkl;'\n", "HJKL:\""],
-            ["bvcxz", "BVCXZ", "nm,./", "NM<>?"],
-        ];
         layout.keyboard_pos.insert(' ', (0, 0, false));
         for (j, row) in layout_str.iter().enumerate() {
             for (i, c) in row[0].char_indices() {
@@ -48,53 +125,84 @@ impl Layout {
                     .insert(c, (i as i16 + 1, j as i16, true));
             }
         }
+        layout.fingers = layout
+            .keyboard_pos
+            .iter()
+            .map(|(&c, &(col, _, _))| (c, Finger::from_column(col)))
+            .collect();
         layout
     }
 
+    /// Position of a key as `(column, row, shift)`, where negative columns are on the left
+    /// hand, column 0 is the space bar, and row 0 is the number row.
+    #[must_use]
+    pub fn position(&self, c: char) -> Option<(i16, i16, bool)> {
+        self.keyboard_pos.get(&c).copied()
+    }
+
+    /// Finger typing a key.
+    #[must_use]
+    pub fn finger(&self, c: char) -> Option<Finger> {
+        self.fingers.get(&c).copied()
+    }
+
+    /// All printable keys with their positions, ordered by row and column.
+    #[must_use]
+    pub fn keys(&self) -> Vec<(char, (i16, i16, bool))> {
+        let mut keys: Vec<_> = self
+            .keyboard_pos
+            .iter()
+            .filter(|(c, _)| !c.is_control())
+            .map(|(&c, &pos)| (c, pos))
+            .collect();
+        keys.sort_by_key(|&(c, (col, row, shift))| (row, shift, col.abs(), col, c));
+        keys
+    }
+
     pub fn redraw(&self, screen: &mut MainScreen, c: Expect) -> Result<()> {
         screen.save()?;
         self.clear(screen)?;
 
-        let (col, row, shift) = *self
-            .keyboard_pos
-            .get(&match c {
-                Expect::Char(c, _) => c,
-                Expect::Softbreak => ' ',
-                Expect::Backspace(_) => '\x08',
-            })
+        let key = match c {
+            Expect::Char(c, _) => Some(c),
+            Expect::Softbreak => Some(' '),
+            Expect::Backspace(_) => Some('\x08'),
+            Expect::Paused => None,
+        };
+        let (col, row, shift) = *key
+            .and_then(|key| self.keyboard_pos.get(&key))
             .unwrap_or(&(0i16, -1i16, false));
+        let finger = key.and_then(|key| self.finger(key));
         let (hit, repeat) = match c {
             Expect::Char(_, repeat) => (true, repeat),
-            Expect::Softbreak => (true, 1),
+            Expect::Softbreak | Expect::Paused => (true, 1),
             Expect::Backspace(repeat) => (false, repeat),
         };
 
-        // left hand
-        screen.move_to(screen.width / 2 - 4, screen.height - 7)?;
-        for i in 0..4 {
-            screen.move_by(-5, 0)?;
-            let (highlight, len, dir) = if col >= 0 {
-                (shift && i == 3, 1, -1)
-            } else {
-                (-col.min(-2).max(-5) == i + 2, 4 - row as u16, col + i + 2)
-            };
-            self.draw_finger(screen, len, dir, highlight, hit, repeat)?;
-        }
-
-        // right hand
-        screen.move_to(screen.width / 2 + 2, screen.height - 7)?;
-        for i in 0..4 {
-            screen.move_by(5, 0)?;
-            let (highlight, len, dir) = if col <= 0 {
-                (shift && i == 3, 1, 1)
-            } else {
-                (col.max(2).min(5) == i + 2, 4 - row as u16, col - i - 2)
-            };
-            self.draw_finger(screen, len, dir, highlight, hit, repeat)?;
+        for (hand, x, step) in [
+            (Hand::Left, screen.width / 2 - 4, -5),
+            (Hand::Right, screen.width / 2 + 2, 5),
+        ] {
+            screen.move_to(x, screen.height - 7)?;
+            for current in Finger::of_hand(hand) {
+                screen.move_by(step, 0)?;
+                let (highlight, len, dir) = match finger {
+                    Some(finger) if finger.hand() == hand && !finger.is_thumb() => {
+                        (finger == current, 4 - row as u16, col - current.column())
+                    }
+                    // the other hand holds Shift with its pinky
+                    _ => (
+                        shift && finger.map(Finger::shift_finger) == Some(current),
+                        1,
+                        step.signum(),
+                    ),
+                };
+                self.draw_finger(screen, len, dir, highlight, hit, repeat)?;
+            }
         }
 
         // thumb
-        if col == 0 && row == 0 {
+        if finger.is_some_and(Finger::is_thumb) {
             screen.move_to(screen.width / 2 - 2, screen.height - 5)?;
             screen.put(
                 format!(
@@ -115,6 +223,14 @@ impl Layout {
         Ok(())
     }
 
+    /// Clear the keyboard off the screen.
+    pub fn hide(&self, screen: &mut MainScreen) -> Result<()> {
+        screen.save()?;
+        self.clear(screen)?;
+        screen.load()?;
+        screen.flush()
+    }
+
     fn clear(&self, screen: &mut MainScreen) -> Result<()> {
         for i in 0..5 {
             screen.move_to(0, screen.height - 7 - i)?;
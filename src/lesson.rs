@@ -0,0 +1,207 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::Result;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{generate::capitalize, layout::Layout, storage::Table, wordlist::WordList};
+
+/// A lesson is passed with at least this accuracy, in percent...
+pub const MIN_ACCURACY: f64 = 95.0;
+/// ...and at least this speed, in words per minute.
+pub const MIN_WPM: f64 = 20.0;
+
+/// Rows of the layout in the order they are learned.
+const ROWS: &[(i16, &str)] = &[
+    (2, "home row"),
+    (1, "top row"),
+    (3, "bottom row"),
+    (0, "number row"),
+];
+
+/// Keys of a row are learned in groups, by the distance of their column from the space bar.
+const GROUPS: &[(&[i16], &str)] = &[
+    (&[2, 3], "index and middle fingers"),
+    (&[4, 5], "ring and little fingers"),
+    (&[1, 6, 7, 8], "stretches"),
+];
+
+/// A step of the touch typing course.
+#[derive(Debug, Clone)]
+pub struct Lesson {
+    /// Position in the course, starting from 1.
+    pub id: usize,
+    pub name: String,
+    /// Keys introduced by this lesson.
+    pub new_keys: Vec<char>,
+    /// Keys unlocked so far, including the new ones.
+    pub keys: Vec<char>,
+}
+
+/// The whole course for a layout: every row finger by finger, then capitals and symbols.
+#[must_use]
+pub fn course(layout: &Layout) -> Vec<Lesson> {
+    let all_keys = layout.keys();
+    let mut groups: Vec<(String, Vec<char>)> = Vec::new();
+    for &(row, row_name) in ROWS {
+        for &(cols, group_name) in GROUPS {
+            let keys: Vec<char> = all_keys
+                .iter()
+                .filter(|(_, (col, r, shift))| *r == row && !shift && cols.contains(&col.abs()))
+                .map(|&(c, _)| c)
+                .collect();
+            groups.push((format!("{row_name}: {group_name}"), keys));
+        }
+    }
+    let (capitals, symbols): (Vec<char>, Vec<char>) = all_keys
+        .iter()
+        .filter(|(c, (_, _, shift))| *shift && *c != ' ')
+        .map(|&(c, _)| c)
+        .partition(|c| c.is_alphabetic());
+    groups.push(("capital letters".to_string(), capitals));
+    groups.push(("symbols".to_string(), symbols));
+
+    let mut keys = vec![' '];
+    let mut lessons = Vec::new();
+    for (name, new_keys) in groups {
+        if new_keys.is_empty() {
+            continue;
+        }
+        keys.extend(&new_keys);
+        lessons.push(Lesson {
+            id: lessons.len() + 1,
+            name,
+            new_keys,
+            keys: keys.clone(),
+        });
+    }
+    lessons
+}
+
+impl Lesson {
+    /// A drill of `n` words using only the unlocked keys, each with at least one new key.
+    ///
+    /// Real words from `words` are preferred, made up ones fill in when there are too few.
+    pub fn drill<R: Rng>(&self, words: &WordList, n: usize, rng: &mut R) -> String {
+        let keys: HashSet<char> = self.keys.iter().copied().collect();
+        let usable = |word: &str| {
+            word.chars().all(|c| keys.contains(&c))
+                && word.chars().any(|c| self.new_keys.contains(&c))
+        };
+        let mut seen = HashSet::new();
+        let real: Vec<String> = words
+            .words
+            .iter()
+            .flat_map(|word| [word.clone(), capitalize(word)])
+            .filter(|word| usable(word) && seen.insert(word.clone()))
+            .collect();
+
+        let others: Vec<char> = self.keys.iter().copied().filter(|&c| c != ' ').collect();
+        (0..n)
+            .map(|_| {
+                if real.len() >= 5 && rng.gen_bool(0.7) {
+                    if let Some(word) = real.choose(rng) {
+                        return word.clone();
+                    }
+                }
+                // made up word, starting with a new key
+                let len = rng.gen_range(2..=5);
+                (0..len)
+                    .filter_map(|i| {
+                        if i == 0 || rng.gen_bool(0.5) {
+                            self.new_keys.choose(rng).copied()
+                        } else {
+                            others.choose(rng).copied()
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The keys introduced by this lesson, for display.
+    #[must_use]
+    pub fn new_keys_str(&self) -> String {
+        self.new_keys
+            .iter()
+            .map(char::to_string)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Best results per lesson, kept between runs.
+#[derive(Debug, Default)]
+pub struct Progress {
+    /// Best `(wpm, accuracy, passed)` per lesson, a passed attempt always beats a failed one.
+    best: BTreeMap<usize, (f64, f64, bool)>,
+}
+
+/// Progress through the course, rewritten after every lesson.
+const TABLE: Table = Table {
+    file: "lessons.tsv",
+    kind: "lessons",
+    version: 1,
+    columns: &["lesson", "wpm", "accuracy", "passed"],
+};
+
+impl Progress {
+    pub fn load() -> Result<Self> {
+        let mut progress = Self::default();
+        for row in TABLE.load()?.rows {
+            if let [id, wpm, accuracy, passed] = &row[..] {
+                if let (Ok(id), Ok(wpm), Ok(accuracy), Ok(passed)) =
+                    (id.parse(), wpm.parse(), accuracy.parse(), passed.parse())
+                {
+                    progress.best.insert(id, (wpm, accuracy, passed));
+                }
+            }
+        }
+        Ok(progress)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let rows: Vec<Vec<String>> = self
+            .best
+            .iter()
+            .map(|(id, (wpm, accuracy, passed))| {
+                vec![
+                    id.to_string(),
+                    format!("{wpm:.2}"),
+                    format!("{accuracy:.2}"),
+                    passed.to_string(),
+                ]
+            })
+            .collect();
+        TABLE.save(&rows)
+    }
+
+    /// Record an attempt at a lesson, returning whether it passed.
+    pub fn record(&mut self, id: usize, wpm: f64, accuracy: f64) -> bool {
+        let passed = wpm >= MIN_WPM && accuracy >= MIN_ACCURACY;
+        let entry = self.best.entry(id).or_insert((0.0, 0.0, false));
+        if passed && !entry.2 || passed == entry.2 && (wpm, accuracy) > (entry.0, entry.1) {
+            *entry = (wpm, accuracy, passed);
+        }
+        passed
+    }
+
+    #[must_use]
+    pub fn is_passed(&self, id: usize) -> bool {
+        self.best.get(&id).is_some_and(|best| best.2)
+    }
+
+    /// Best `(wpm, accuracy)` of a lesson, if it was ever attempted.
+    #[must_use]
+    pub fn best(&self, id: usize) -> Option<(f64, f64)> {
+        self.best
+            .get(&id)
+            .map(|&(wpm, accuracy, _)| (wpm, accuracy))
+    }
+
+    /// The last unlocked lesson, every lesson before it has been passed.
+    #[must_use]
+    pub fn unlocked(&self) -> usize {
+        (1..).find(|&id| !self.is_passed(id)).unwrap_or(1)
+    }
+}
@@ -1,5 +1,29 @@
+pub mod analyze;
+pub mod blind;
+pub mod challenge;
+pub mod chart;
+pub mod dashboard;
+pub mod date;
+pub mod excerpt;
+pub mod export;
+pub mod finger;
+pub mod generate;
+pub mod history;
+pub mod keylog;
 pub mod layout;
+pub mod lesson;
+pub mod metronome;
+pub mod ngram;
 pub mod pecker;
+pub mod quote;
+pub mod review;
+pub mod rhythm;
 pub mod screen;
+pub mod session;
+pub mod source;
 pub mod stat;
+pub mod storage;
+pub mod terminal;
 pub mod text;
+pub mod typo;
+pub mod wordlist;
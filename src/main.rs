@@ -1,49 +1,653 @@
-use std::fs::File;
-use std::io::{Read, Result};
+use std::io::{stdout, Error, ErrorKind, Result, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
 
-use clap::Parser;
-use rand::{seq::IteratorRandom, thread_rng};
+use clap::{ArgGroup, Parser, Subcommand};
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
-use pecker::pecker::Pecker;
+use pecker::{
+    analyze::{self, Analysis},
+    blind,
+    challenge::{self, Challenge, Score},
+    dashboard::Dashboard,
+    date::Date,
+    excerpt::{Excerpt, LineRange},
+    export::{self, Format, Table},
+    finger,
+    generate::{self, Decoration, Generator, Seeds},
+    history::{History, Record},
+    keylog,
+    layout::{Hints, Layout, LAYOUTS},
+    lesson::{self, Lesson, Progress, MIN_ACCURACY, MIN_WPM},
+    metronome::Metronome,
+    ngram,
+    pecker::{Exit, Pecker},
+    quote,
+    review::Deck,
+    rhythm::Rhythm,
+    session::{Pace, PracticeText, Session},
+    source::TextSource,
+    terminal::TerminalGuard,
+    typo,
+    wordlist::{self, WordList},
+};
 
 #[derive(Parser)]
 #[command(author, version, about)]
+#[command(group(ArgGroup::new("excerpt").multiple(false)))]
+#[command(group(ArgGroup::new("challenge").multiple(false)))]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
-    file: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Text files or directories to practice on, `-` reads from stdin
+    files: Vec<String>,
+    /// Practice a random paragraph instead of a whole file
+    #[arg(short, long, group = "excerpt")]
+    paragraph: bool,
+    /// Practice the n-th paragraph
+    #[arg(long, value_name = "N", group = "excerpt")]
+    nth_paragraph: Option<usize>,
+    /// Practice a range of lines, e.g. `40:80`
+    #[arg(long, value_name = "START:END", group = "excerpt")]
+    lines: Option<LineRange>,
+    /// Practice a random excerpt of about N lines
+    #[arg(long, value_name = "N", group = "excerpt")]
+    excerpt_lines: Option<usize>,
+    /// Practice a random excerpt of about N words
+    #[arg(long, value_name = "N", group = "excerpt")]
+    excerpt_words: Option<usize>,
+    /// Practice the definition of a function
+    #[arg(long, value_name = "NAME", group = "excerpt")]
+    function: Option<String>,
+    /// End the session after this many seconds
+    #[arg(short, long, value_name = "SECONDS", global = true)]
+    time_limit: Option<u64>,
+    /// Race a marker moving at this many wpm, or `auto` for a bit above the recent average
+    #[arg(long, value_name = "WPM|auto", global = true)]
+    pace: Option<Pace>,
+    /// Type along with a beat at this many beats per minute
+    #[arg(long, value_name = "BPM", global = true, value_parser = bpm)]
+    metronome: Option<f64>,
+    /// Ring the terminal bell on each beat of the metronome
+    #[arg(long, requires = "metronome", global = true)]
+    bell: bool,
+    /// Do not show which chars are right or wrong until the end, wrong ones are passed over
+    #[arg(long, conflicts_with = "challenge", global = true)]
+    blind: bool,
+    /// When to show the keyboard with the finger to use: always, never, after-miss or
+    /// hesitation[:MS] (after a pause on a char, 1000ms by default)
+    #[arg(long, value_name = "WHEN", default_value = "always", global = true)]
+    keyboard: Hints,
+    /// Challenge: end the session on the first miss
+    #[arg(long, group = "challenge", global = true)]
+    sudden_death: bool,
+    /// Challenge: fail the session once the accuracy drops below this many percent
+    #[arg(long, value_name = "PERCENT", group = "challenge", global = true)]
+    #[arg(value_parser = percent)]
+    min_accuracy: Option<f64>,
+    /// Challenge: fail the session if it ends below this speed
+    #[arg(long, value_name = "WPM", group = "challenge", global = true)]
+    #[arg(value_parser = wpm)]
+    min_wpm: Option<f64>,
+    /// Word list for random texts and lessons, see `pecker wordlist`
+    #[arg(short, long, value_name = "NAME", global = true)]
+    wordlist: Option<String>,
+    /// Probability of capitalizing a word in random texts
+    #[arg(long, value_name = "P", default_value_t = 0.0, value_parser = probability)]
+    capitals: f64,
+    /// Probability of a punctuation mark after a word in random texts
+    #[arg(long, value_name = "P", default_value_t = 0.0, value_parser = probability)]
+    punctuation: f64,
+    /// Probability of wrapping a word in quotes or brackets in random texts
+    #[arg(long, value_name = "P", default_value_t = 0.0, value_parser = probability)]
+    brackets: f64,
+    /// Probability of a number before a word in random texts
+    #[arg(long, value_name = "P", default_value_t = 0.0, value_parser = probability)]
+    numbers: f64,
+    /// Seed for random texts, the same seed gives the same texts
+    #[arg(long, conflicts_with = "daily")]
+    seed: Option<u64>,
+    /// Daily challenge, the same texts for everyone on the same day
+    #[arg(long)]
+    daily: bool,
+    /// Practice the text of a session from the history again
+    #[arg(long, value_name = "ID")]
+    replay: Option<usize>,
+    /// Practice quotes, of a length (short, medium, long) or a specific one by id
+    #[arg(short, long, value_name = "LENGTH|ID", num_args = 0..=1, default_missing_value = "any")]
+    #[arg(conflicts_with_all = ["files", "replay"])]
+    quote: Option<String>,
+    /// Practice a lesson of the touch typing course, the latest unlocked one by default
+    #[arg(short, long, value_name = "N", num_args = 0..=1)]
+    #[arg(conflicts_with_all = ["files", "replay", "quote"])]
+    lesson: Option<Option<usize>>,
+    /// Drill bigrams and trigrams: `common`, `same-finger`, `row-jump` or a list like `th,ing`
+    #[arg(short, long, value_name = "NGRAMS")]
+    #[arg(conflicts_with_all = ["files", "replay", "quote", "lesson"])]
+    ngrams: Option<String>,
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+fn probability(s: &str) -> std::result::Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(p) if (0.0..=1.0).contains(&p) => Ok(p),
+        _ => Err(format!("`{s}` is not a probability between 0 and 1")),
+    }
+}
+
+fn percent(s: &str) -> std::result::Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(p) if (0.0..=100.0).contains(&p) => Ok(p),
+        _ => Err(format!("`{s}` is not a percentage between 0 and 100")),
+    }
+}
+
+fn wpm(s: &str) -> std::result::Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(wpm) if (1.0..=1000.0).contains(&wpm) => Ok(wpm),
+        _ => Err(format!("`{s}` is not a speed between 1 and 1000 wpm")),
+    }
+}
+
+fn bpm(s: &str) -> std::result::Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(bpm) if (1.0..=1000.0).contains(&bpm) => Ok(bpm),
+        _ => Err(format!("`{s}` is not a tempo between 1 and 1000 bpm")),
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the available word lists, or register a new one
+    Wordlist {
+        #[command(subcommand)]
+        action: Option<WordlistAction>,
+    },
+    /// Show the touch typing course and its progress
+    Lessons,
+    /// Analyze how texts would be typed on the built-in layouts
+    Analyze {
+        /// Text files to analyze, `-` reads from stdin
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Only analyze this layout instead of comparing all of them
+        #[arg(long, value_name = "NAME")]
+        layout: Option<String>,
+    },
+    /// Export the history, per-key aggregates and key strokes, see the README for the schema
+    Export {
+        /// Output format, csv or json
+        #[arg(short, long, default_value = "csv")]
+        format: Format,
+        /// Only export one table: sessions, keys or keystrokes; csv exports sessions by default
+        #[arg(long)]
+        table: Option<Table>,
+        /// Only export sessions since this date, as YYYY-MM-DD
+        #[arg(long, value_name = "DATE")]
+        since: Option<Date>,
+    },
+    /// Show charts of the progress over the last sessions
+    Stats {
+        /// Number of sessions to chart
+        #[arg(short = 'n', long, default_value_t = 50)]
+        sessions: usize,
+    },
+    /// Review the problem words that are due, mixed with new words
+    Review {
+        /// List the problem words and when they are due instead
+        #[arg(long)]
+        list: bool,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum WordlistAction {
+    /// Register a word list file under a name
+    Add { name: String, file: PathBuf },
+}
 
+impl Cli {
+    fn excerpt(&self) -> Excerpt {
+        if self.paragraph {
+            Excerpt::RandomParagraph
+        } else if let Some(n) = self.nth_paragraph {
+            Excerpt::Paragraph(n)
+        } else if let Some(range) = self.lines {
+            Excerpt::Lines(range)
+        } else if let Some(n) = self.excerpt_lines {
+            Excerpt::RandomLines(n)
+        } else if let Some(n) = self.excerpt_words {
+            Excerpt::RandomWords(n)
+        } else if let Some(name) = &self.function {
+            Excerpt::Function(name.clone())
+        } else {
+            Excerpt::Whole
+        }
+    }
+
+    fn challenge(&self) -> Option<Challenge> {
+        if self.sudden_death {
+            Some(Challenge::SuddenDeath)
+        } else if let Some(min) = self.min_accuracy {
+            Some(Challenge::MinAccuracy(min))
+        } else {
+            self.min_wpm.map(Challenge::MinWpm)
+        }
+    }
+
+    fn decoration(&self) -> Decoration {
+        Decoration {
+            capitalize: self.capitals,
+            punctuation: self.punctuation,
+            brackets: self.brackets,
+            numbers: self.numbers,
+        }
+    }
+}
+
+fn run_wordlist(action: Option<WordlistAction>) -> Result<()> {
+    let mut out = stdout().lock();
+    match action {
+        Some(WordlistAction::Add { name, file }) => {
+            let path = wordlist::register(&name, &file)?;
+            writeln!(out, "registered `{name}` at {}", path.display())?;
+        }
+        None => {
+            for (name, description) in wordlist::available()? {
+                writeln!(out, "{name:<16}{description}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_lessons() -> Result<()> {
+    let progress = Progress::load()?;
+    let mut out = stdout().lock();
+    for lesson in lesson::course(&Layout::new()) {
+        let mark = if progress.is_passed(lesson.id) {
+            "v"
+        } else if lesson.id == progress.unlocked() {
+            ">"
+        } else {
+            " "
+        };
+        let best = progress
+            .best(lesson.id)
+            .map(|(wpm, accuracy)| format!("best {wpm:.0} wpm, {accuracy:.1}%"))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{mark} {:>2}  {:<40}{:<28}{best}",
+            lesson.id,
+            lesson.name,
+            lesson.new_keys_str()
+        )?;
+    }
+    Ok(())
+}
+
+fn run_analyze(files: &[String], layout: Option<&str>) -> Result<()> {
     let mut text = String::new();
-    let mut align_center = true;
+    for file in files {
+        if file == "-" {
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut text)?;
+        } else {
+            text.push_str(&std::fs::read_to_string(file)?);
+        }
+    }
+    let names: Vec<&str> = match layout {
+        Some(name) if Layout::named(name).is_none() => {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "no layout `{name}`, try one of {}",
+                    LAYOUTS
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ));
+        }
+        Some(name) => vec![name],
+        None => LAYOUTS.iter().map(|(name, _)| *name).collect(),
+    };
+    let analyses: Vec<(&str, Analysis)> = names
+        .into_iter()
+        .filter_map(|name| Some((name, Analysis::new(&text, &Layout::named(name)?))))
+        .collect();
+    let mut out = stdout().lock();
+    for line in analyze::report(&analyses) {
+        writeln!(out, "{line}")?;
+    }
+    Ok(())
+}
+
+fn run_review_list() -> Result<()> {
+    let today = Date::today().days();
+    let deck = Deck::load()?;
+    let mut cards: Vec<_> = deck.cards.values().collect();
+    cards.sort_by_key(|card| card.due);
+    let mut out = stdout().lock();
+    for card in cards {
+        let due = if card.due <= today {
+            "due".to_string()
+        } else {
+            format!("in {} days", card.due - today)
+        };
+        writeln!(
+            out,
+            "{:<24}{:<14}ease {:.2}, {} lapses",
+            card.word, due, card.ease, card.lapses
+        )?;
+    }
+    writeln!(
+        out,
+        "{} due of {} words",
+        deck.due(today).len(),
+        deck.cards.len()
+    )
+}
 
-    if let Some(f) = cli.file {
-        if !f.ends_with(".txt") {
-            align_center = false;
+/// Keep track of a finished lesson, and move on to the next one once it is passed.
+fn finish_lesson(lesson: &mut Lesson, record: &Record) -> Result<String> {
+    let mut progress = Progress::load()?;
+    let passed = progress.record(lesson.id, record.wpm, record.accuracy);
+    progress.save()?;
+    if !passed {
+        return Ok(format!(
+            "lesson {} is passed with {MIN_WPM:.0} wpm at {MIN_ACCURACY:.0}% accuracy",
+            lesson.id
+        ));
+    }
+    match lesson::course(&Layout::new()).into_iter().nth(lesson.id) {
+        Some(next) => {
+            let message = format!(
+                "lesson {} passed, up next: {} ({})",
+                lesson.id,
+                next.name,
+                next.new_keys_str()
+            );
+            *lesson = next;
+            Ok(message)
+        }
+        None => Ok("lesson passed, the course is complete!".to_string()),
+    }
+}
+
+/// Keep the score of a challenge, and tell how it went along with its high scores.
+fn finish_challenge(challenge: Challenge, session: &Session) -> Result<Vec<String>> {
+    let elapsed = session.timer.elapsed();
+    let mut lines = vec![match &session.failure {
+        Some(reason) => format!("{} failed: {reason}", challenge.name()),
+        None => format!("{} passed", challenge.name()),
+    }];
+    let score = challenge
+        .score(&session.stat, elapsed, session.failure.is_some())
+        .map(|score| {
+            Score::new(
+                challenge,
+                score,
+                &session.stat,
+                elapsed,
+                &session.practice.source,
+            )
+        });
+    if let Some(score) = &score {
+        challenge::append(score)?;
+    }
+    lines.extend(challenge::table(
+        challenge,
+        &challenge::load()?,
+        challenge::TOP,
+        score.as_ref(),
+    ));
+    Ok(lines)
+}
+
+fn generator(cli: &Cli) -> Result<Generator> {
+    let wordlist = cli.wordlist.as_deref();
+    let generator = if let Some(Command::Review { .. }) = cli.command {
+        Generator::Review {
+            words: WordList::load(wordlist.unwrap_or(wordlist::DEFAULT))?,
+            n_words: 20,
+        }
+    } else if let Some(id) = cli.replay {
+        let history = History::load()?;
+        let record = history.get(id).ok_or_else(|| {
+            Error::new(ErrorKind::NotFound, format!("no session #{id} in history"))
+        })?;
+        Generator::Fixed(PracticeText {
+            text: record.text.clone(),
+            align_center: record.align_center,
+            source: record.source.clone(),
+            seed: record.seed,
+        })
+    } else if let Some(which) = &cli.quote {
+        if let Ok(id) = which.parse() {
+            let quote = quote::get(id).ok_or_else(|| {
+                Error::new(ErrorKind::NotFound, format!("no quote #{id} in the corpus"))
+            })?;
+            Generator::Fixed(quote.practice())
+        } else if which == "any" {
+            Generator::Quotes(None)
+        } else {
+            let length = which
+                .parse()
+                .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+            Generator::Quotes(Some(length))
+        }
+    } else if let Some(id) = cli.lesson {
+        let course = lesson::course(&Layout::new());
+        let unlocked = Progress::load()?.unlocked().min(course.len());
+        let id = id.unwrap_or(unlocked);
+        if id > unlocked {
+            return Err(Error::new(
+                ErrorKind::PermissionDenied,
+                format!("lesson {id} is locked, pass lesson {unlocked} first"),
+            ));
+        }
+        let lesson = id.checked_sub(1).and_then(|i| course.into_iter().nth(i));
+        let lesson = lesson.ok_or_else(|| {
+            Error::new(ErrorKind::NotFound, format!("no lesson {id} in the course"))
+        })?;
+        Generator::Lesson {
+            lesson,
+            // the more words, the more of them fit the few unlocked keys
+            words: WordList::load(wordlist.unwrap_or("english-10k"))?,
+            n_words: 20,
+        }
+    } else if let Some(spec) = &cli.ngrams {
+        let targets = ngram::targets(spec, &Layout::new());
+        if targets.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("no n-grams to drill in `{spec}`"),
+            ));
+        }
+        Generator::Ngrams {
+            targets,
+            words: WordList::load(wordlist.unwrap_or("english-10k"))?,
+            n_words: 20,
         }
-        // read content from file
-        let mut f = File::open(&f)?;
-        f.read_to_string(&mut text)?;
+    } else if !cli.files.is_empty() {
+        Generator::Source(TextSource::new(&cli.files, cli.excerpt())?)
     } else {
-        let mut rng = thread_rng();
-        let words = include_str!("google-10000-english-usa-no-swears.txt");
-        for word in words
-            .split_whitespace()
-            .take(1000)
-            .choose_multiple(&mut rng, 20)
-        {
-            text.push_str(word);
-            text.push(' ');
+        Generator::Words {
+            words: WordList::load(wordlist.unwrap_or(wordlist::DEFAULT))?,
+            n_words: 20,
+            decoration: cli.decoration(),
         }
+    };
+    Ok(generator)
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        // the output was cut short on purpose, e.g. piped into `head`
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("pecker: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::parse();
+
+    match &cli.command {
+        Some(Command::Wordlist { action }) => return run_wordlist(action.clone()),
+        Some(Command::Lessons) => return run_lessons(),
+        Some(Command::Stats { sessions }) => {
+            let history = History::load()?;
+            let entries = keylog::load()?;
+            let _guard = TerminalGuard::new()?;
+            return Dashboard::new(&history.records, entries, *sessions).run();
+        }
+        Some(Command::Export {
+            format,
+            table,
+            since,
+        }) => {
+            let history = History::load()?;
+            let entries = keylog::load()?;
+            return stdout().lock().write_all(
+                export::export(&history.records, &entries, *format, *table, *since).as_bytes(),
+            );
+        }
+        Some(Command::Analyze { files, layout }) => return run_analyze(files, layout.as_deref()),
+        Some(Command::Review { list: true }) => return run_review_list(),
+        Some(Command::Review { list: false }) | None => (),
     }
 
+    // read everything up front, stdin in particular must be drained before raw mode
+    let mut generator = generator(&cli)?;
+    let mut seeds = if cli.daily {
+        Seeds::daily(Date::today())
+    } else {
+        Seeds::new(cli.seed.unwrap_or_else(|| thread_rng().gen()))
+    };
+    let mut practice = generator.generate(seeds.next_seed())?;
+
+    // the terminal is restored when the guard goes out of scope, or on panic
+    let _guard = TerminalGuard::new()?;
+
     // initialize pecker
     let mut pecker = Pecker::new();
-    pecker.reset(text.trim_end(), align_center)?;
+    pecker.session.time_limit = cli.time_limit.map(Duration::from_secs);
+    pecker.session.metronome = cli.metronome.map(|bpm| Metronome::new(bpm, cli.bell));
+    pecker.session.text_lines.blind = cli.blind;
+    pecker.hints = cli.keyboard;
+    pecker.session.challenge = cli.challenge();
 
-    // start main event loop
-    pecker.start()?;
+    // start main event loop, until the user stops asking for a new text
+    loop {
+        if let Some(pace) = cli.pace {
+            // the automatic pace follows the sessions as they are finished
+            pecker.session.pace = Some(pace.target(&History::load()?));
+        }
+        pecker.reset(practice.clone())?;
+        let mut exit = pecker.start()?;
+        // some challenges are only decided at the end, and failing them there is failing all the
+        // same: nothing is recorded but the score
+        if exit == Exit::Finish && pecker.session.check_challenge(true) {
+            exit = Exit::Fail;
+        }
+        if exit == Exit::Finish {
+            let record = History::append(&pecker.session.record())?;
+            keylog::append(&keylog::entries(
+                record.id,
+                &record.text,
+                &pecker.session.stat,
+            ))?;
+            let mut lines = record.summary();
+            let rhythm = Rhythm::new(&pecker.session.stat, pecker.session.timer.elapsed());
+            lines.splice(2..2, rhythm.summary());
+            if let Some(challenge) = pecker.session.challenge {
+                lines.push(String::new());
+                lines.extend(finish_challenge(challenge, &pecker.session)?);
+            }
+            let problem_words = pecker.session.problem_words();
+            let mut deck = Deck::load()?;
+            deck.update(
+                &pecker.session.text_lines,
+                &problem_words,
+                Date::today().days(),
+            );
+            deck.save()?;
+            if let Generator::Lesson { lesson, .. } = &mut generator {
+                lines.push(String::new());
+                lines.push(finish_lesson(lesson, &record)?);
+            }
+            if let Generator::Ngrams { targets, .. } = &generator {
+                let stat = &pecker.session.stat;
+                let speeds = ngram::speeds(&record.text, &stat.hits, &stat.misses, targets);
+                lines.push(String::new());
+                lines.extend(ngram::summary(&speeds));
+            }
+            if let Some(metronome) = &pecker.session.metronome {
+                let timing = metronome.timing(&pecker.session.stat.keystrokes);
+                lines.push(String::new());
+                lines.push(timing.summary(metronome.bpm));
+            }
+            if cli.blind {
+                lines.push(String::new());
+                lines.extend(blind::summary(&blind::errors(
+                    &record.text,
+                    &pecker.session.stat,
+                )));
+            }
+            let layout = Layout::new();
+            let typos = typo::breakdown(&record.text, &pecker.session.stat.typos, &layout);
+            if let Some(line) = typo::summary(&typos) {
+                lines.push(String::new());
+                lines.push(line);
+            }
+            let fingers = finger::finger_stats(&record.text, &pecker.session.stat, &layout);
+            lines.push(String::new());
+            lines.extend(finger::summary(&fingers));
+            if !problem_words.is_empty() {
+                lines.push(String::new());
+                lines.push(format!("problem words: {}", problem_words.join(" ")));
+            }
+            exit = pecker.results(&lines)?;
+        }
+        if let (Exit::Fail, Some(challenge)) = (exit, pecker.session.challenge) {
+            // failed sessions stay out of the history, the keystroke log and the review deck
+            let mut lines = finish_challenge(challenge, &pecker.session)?;
+            let stat = &pecker.session.stat;
+            lines.insert(
+                1,
+                format!(
+                    "{:.0} wpm | {:.1}% accuracy",
+                    stat.wpm(pecker.session.timer.elapsed()),
+                    stat.accuracy()
+                ),
+            );
+            lines.insert(2, String::new());
+            exit = pecker.results(&lines)?;
+        }
+        match exit {
+            Exit::Next => practice = generator.generate(seeds.next_seed())?,
+            Exit::Retry => (),
+            Exit::Drill => {
+                let seed = seeds.next_seed();
+                let words = pecker.session.problem_words();
+                practice = PracticeText {
+                    text: generate::repeat_words(&words, 3, &mut ChaCha8Rng::seed_from_u64(seed)),
+                    align_center: true,
+                    source: "drill:problem words".to_string(),
+                    seed: None,
+                };
+            }
+            Exit::Finish | Exit::Fail | Exit::Quit => break,
+        }
+    }
 
     Ok(())
 }
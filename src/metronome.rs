@@ -0,0 +1,127 @@
+use std::time::{Duration, Instant};
+
+use crate::stat::Keystroke;
+
+/// Share of the beat period within which a key stroke counts as on the beat.
+const ON_BEAT: f64 = 0.1;
+
+/// Beats in a bar, the first one is drawn heavier.
+pub const BAR: u64 = 4;
+
+/// A steady beat to type along with, from the start of the session.
+#[derive(Debug, Clone)]
+pub struct Metronome {
+    pub bpm: f64,
+    /// Whether to ring the terminal bell on each beat.
+    pub bell: bool,
+    origin: Instant,
+    /// Index of the last beat that was shown.
+    last_beat: Option<u64>,
+}
+
+impl Metronome {
+    #[must_use]
+    pub fn new(bpm: f64, bell: bool) -> Self {
+        Self {
+            bpm,
+            bell,
+            origin: Instant::now(),
+            last_beat: None,
+        }
+    }
+
+    /// Start counting beats again from `time`.
+    pub fn reset(&mut self, time: Instant) {
+        self.origin = time;
+        self.last_beat = None;
+    }
+
+    #[must_use]
+    pub fn period(&self) -> Duration {
+        Duration::from_secs_f64(60.0 / self.bpm)
+    }
+
+    fn beat_at(&self, time: Instant) -> u64 {
+        (time.saturating_duration_since(self.origin).as_secs_f64() / self.period().as_secs_f64())
+            as u64
+    }
+
+    /// Time from `time` until the next beat.
+    #[must_use]
+    pub fn until_next_beat(&self, time: Instant) -> Duration {
+        let next = self.origin + self.period().mul_f64((self.beat_at(time) + 1) as f64);
+        next.saturating_duration_since(time)
+    }
+
+    /// The beat reached at `time`, if it was not shown yet.
+    pub fn tick(&mut self, time: Instant) -> Option<u64> {
+        let beat = self.beat_at(time);
+        if self.last_beat == Some(beat) {
+            return None;
+        }
+        self.last_beat = Some(beat);
+        Some(beat)
+    }
+
+    /// Signed offset of `time` from the nearest beat, in seconds, negative when early.
+    #[must_use]
+    pub fn offset(&self, time: Instant) -> f64 {
+        let period = self.period().as_secs_f64();
+        let since = time.saturating_duration_since(self.origin).as_secs_f64();
+        let offset = since % period;
+        if offset > period / 2.0 {
+            offset - period
+        } else {
+            offset
+        }
+    }
+
+    /// How closely the chars typed into the text landed on the beat.
+    ///
+    /// Backspaces are corrections rather than part of the rhythm, and keys pressed while paused
+    /// only resume the session, so neither has a position in the text and neither is scored.
+    #[must_use]
+    pub fn timing(&self, keystrokes: &[Keystroke]) -> Timing {
+        let offsets: Vec<f64> = keystrokes
+            .iter()
+            .filter(|s| s.position.is_some())
+            .map(|s| self.offset(s.pressed))
+            .collect();
+        let n = offsets.len() as f64;
+        if offsets.is_empty() {
+            return Timing::default();
+        }
+        let limit = self.period().as_secs_f64() * ON_BEAT;
+        Timing {
+            on_beat: offsets.iter().filter(|o| o.abs() <= limit).count() as f64 * 100.0 / n,
+            mean_offset: Duration::from_secs_f64(offsets.iter().map(|o| o.abs()).sum::<f64>() / n),
+            bias: offsets.iter().sum::<f64>() / n,
+        }
+    }
+}
+
+/// How closely the key strokes of a session landed on the beat.
+#[derive(Debug, Clone, Default)]
+pub struct Timing {
+    /// Key strokes within `ON_BEAT` of the period from a beat, in percent.
+    pub on_beat: f64,
+    /// Average distance to the nearest beat.
+    pub mean_offset: Duration,
+    /// Average signed offset in seconds, negative when rushing ahead of the beat.
+    pub bias: f64,
+}
+
+impl Timing {
+    /// A line for the results screen.
+    #[must_use]
+    pub fn summary(&self, bpm: f64) -> String {
+        let bias_ms = self.bias * 1000.0;
+        let tendency = if bias_ms < 0.0 { "early" } else { "late" };
+        format!(
+            "metronome {bpm:.0} bpm: {:.0}% on the beat | off by {}ms on average, {:.0}ms {tendency}",
+            self.on_beat,
+            self.mean_offset.as_millis(),
+            bias_ms.abs()
+        )
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{finger::Finger, layout::Layout, wordlist::WordList};
+
+/// Frequent English bigrams and trigrams, drilled by `--ngrams common`.
+pub const COMMON: &[&str] = &[
+    "th", "he", "in", "er", "an", "re", "on", "at", "en", "nd", "ti", "es", "or", "te", "of", "ed",
+    "is", "it", "al", "ar", "st", "to", "nt", "ng", "the", "and", "ing", "ion", "tio", "ent",
+    "ati", "for", "her", "ter", "hat", "tha", "ere", "ate", "his", "con",
+];
+
+/// How many of the targets a single text focuses on.
+const TARGETS_PER_TEXT: usize = 4;
+
+/// Letter bigrams of the layout matching a finger transition.
+fn transitions(
+    layout: &Layout,
+    matches: impl Fn((Finger, i16), (Finger, i16)) -> bool,
+) -> Vec<String> {
+    let letters: Vec<(char, (Finger, i16))> = layout
+        .keys()
+        .into_iter()
+        .filter(|(c, (_, _, shift))| c.is_alphabetic() && !shift)
+        .filter_map(|(c, (_, row, _))| Some((c, (layout.finger(c)?, row))))
+        .collect();
+    let mut bigrams = Vec::new();
+    for &(a, pos_a) in &letters {
+        for &(b, pos_b) in &letters {
+            if a != b && matches(pos_a, pos_b) {
+                bigrams.push(format!("{a}{b}"));
+            }
+        }
+    }
+    bigrams
+}
+
+/// N-grams to drill: `common`, `same-finger`, `row-jump`, or a comma separated list.
+#[must_use]
+pub fn targets(spec: &str, layout: &Layout) -> Vec<String> {
+    match spec {
+        "common" => COMMON.iter().map(|s| s.to_string()).collect(),
+        // two different keys typed by the same finger
+        "same-finger" => transitions(layout, |(finger_a, _), (finger_b, _)| {
+            !finger_a.is_thumb() && finger_a == finger_b
+        }),
+        // the same hand jumping over the home row, between top and bottom rows
+        "row-jump" => transitions(layout, |(finger_a, row_a), (finger_b, row_b)| {
+            finger_a.hand() == finger_b.hand() && (row_a - row_b).abs() >= 2
+        }),
+        _ => spec
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+/// A drill of `n` words focusing on a few of the `targets`, mixing real words that contain
+/// them with the bare n-grams.
+pub fn drill<R: Rng>(targets: &[String], words: &WordList, n: usize, rng: &mut R) -> String {
+    let focus: Vec<&String> = targets.choose_multiple(rng, TARGETS_PER_TEXT).collect();
+    let containing: Vec<Vec<&String>> = focus
+        .iter()
+        .map(|target| {
+            words
+                .words
+                .iter()
+                .filter(|word| word.contains(target.as_str()))
+                .collect()
+        })
+        .collect();
+    (0..n)
+        .filter_map(|_| {
+            let i = rng.gen_range(0..focus.len().max(1));
+            let target = focus.get(i)?;
+            match containing[i].choose(rng) {
+                Some(word) if rng.gen_bool(0.6) => Some(word.to_string()),
+                _ => Some(target.to_string()),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Typing speed of an n-gram over a session.
+#[derive(Debug, Clone)]
+pub struct NgramSpeed {
+    pub ngram: String,
+    /// Occurrences typed without a mistake.
+    pub count: usize,
+    /// Average time from the first to the last key of the n-gram.
+    pub average: Duration,
+}
+
+/// Speed of each of the `ngrams` found in `text`, from the hits and misses of a session.
+///
+/// Occurrences with a mistake inside are left out, the slowest n-grams come first.
+#[must_use]
+pub fn speeds(
+    text: &str,
+    hits: &[(usize, Duration)],
+    misses: &[(usize, Duration)],
+    ngrams: &[String],
+) -> Vec<NgramSpeed> {
+    let text: Vec<char> = text.chars().collect();
+    let times: HashMap<usize, Duration> = hits.iter().copied().collect();
+    let missed: HashSet<usize> = misses.iter().map(|&(position, _)| position).collect();
+
+    let mut result = Vec::new();
+    for ngram in ngrams {
+        let chars: Vec<char> = ngram.chars().collect();
+        if chars.len() < 2 || chars.len() > text.len() {
+            continue;
+        }
+        let mut total = Duration::ZERO;
+        let mut count = 0;
+        for start in 0..=text.len() - chars.len() {
+            let end = start + chars.len() - 1;
+            if text[start..=end] != chars[..] || (start..=end).any(|p| missed.contains(&p)) {
+                continue;
+            }
+            if let (Some(first), Some(last)) = (times.get(&start), times.get(&end)) {
+                total += last.saturating_sub(*first);
+                count += 1;
+            }
+        }
+        if count > 0 {
+            result.push(NgramSpeed {
+                ngram: ngram.clone(),
+                count,
+                average: total / count as u32,
+            });
+        }
+    }
+    result.sort_by_key(|speed| std::cmp::Reverse(speed.average));
+    result
+}
+
+/// Lines for the results screen, with the slowest n-grams.
+#[must_use]
+pub fn summary(speeds: &[NgramSpeed]) -> Vec<String> {
+    speeds
+        .chunks(4)
+        .take(2)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|speed| {
+                    format!(
+                        "{} {} ms (x{})",
+                        speed.ngram,
+                        speed.average.as_millis(),
+                        speed.count
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" | ")
+        })
+        .collect()
+}
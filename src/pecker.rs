@@ -1,130 +1,443 @@
 use std::io::Result;
+use std::time::{Duration, Instant};
 
-use crate::layout::Layout;
+use crate::layout::{Hints, Layout};
 
 use crossterm::{
-    event::{read, Event, KeyCode, KeyModifiers},
-    terminal::enable_raw_mode,
+    event::{poll, read, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    style::{StyledContent, Stylize},
 };
 
 use crate::{
+    metronome,
     screen::{MainScreen, Styled},
-    text::{State, TextLines},
+    session::{PracticeText, Session},
+    text::{Expect, State},
 };
 
+/// How often the status bar is refreshed while no key is pressed.
+const TICK: Duration = Duration::from_millis(200);
+
+/// Why the main event loop returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// The whole text has been typed.
+    Finish,
+    /// The user asked to quit.
+    Quit,
+    /// The user asked for a fresh text.
+    Next,
+    /// The user asked to type the same text again.
+    Retry,
+    /// The user asked to drill the problem words of the last text.
+    Drill,
+    /// The challenge of the session was failed, along the way or at the end.
+    Fail,
+}
+
 pub struct Pecker {
     screen: MainScreen,
-    text_lines: TextLines,
     layout: Layout,
+    pub session: Session,
+    /// When to show the keyboard.
+    pub hints: Hints,
+    /// Whether the keyboard is on screen.
+    hint_shown: bool,
+    /// Whether the last char typed was wrong.
+    missed: bool,
+    /// When the last key was pressed, or the text was started.
+    last_key: Instant,
+    tab_pending: bool,
 }
 
 impl Pecker {
     #[must_use]
     pub fn new() -> Self {
         let screen = MainScreen::new();
-        let text_lines = TextLines::new();
         let layout = Layout::new();
+        let session = Session::new();
         Self {
             screen,
-            text_lines,
             layout,
+            session,
+            hints: Hints::default(),
+            hint_shown: false,
+            missed: false,
+            last_key: Instant::now(),
+            tab_pending: false,
+        }
+    }
+
+    pub fn reset(&mut self, practice: PracticeText) -> Result<()> {
+        let expect = self.session.reset(practice, self.screen.width);
+        self.missed = false;
+        self.last_key = Instant::now();
+        self.redraw(expect)
+    }
+
+    /// Start over on the same text, keeping the terminal as it is.
+    pub fn restart(&mut self) -> Result<()> {
+        let expect = self.session.restart(self.screen.width);
+        self.missed = false;
+        self.last_key = Instant::now();
+        self.redraw(expect)
+    }
+
+    fn redraw(&mut self, expect: Expect) -> Result<()> {
+        self.session.text_lines.redraw(&mut self.screen)?;
+        self.update_pace()?;
+        self.hint(expect)?;
+        self.screen.status(&self.session.status())
+    }
+
+    /// Show the key to type next on the keyboard, if hints are wanted right now.
+    fn hint(&mut self, expect: Expect) -> Result<()> {
+        self.hint_shown = match self.hints {
+            Hints::Always => true,
+            Hints::Never => false,
+            Hints::AfterMiss => self.missed,
+            Hints::Hesitation(delay) => self.last_key.elapsed() >= delay,
+        };
+        if self.hint_shown {
+            self.layout.redraw(&mut self.screen, expect)
+        } else {
+            self.layout.hide(&mut self.screen)
+        }
+    }
+
+    /// Time until the keyboard is shown for hesitating, if it is to be shown.
+    fn until_hesitation(&self) -> Option<Duration> {
+        match self.hints {
+            Hints::Hesitation(delay) if !self.hint_shown && !self.session.paused => {
+                Some((self.last_key + delay).saturating_duration_since(Instant::now()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Move the pace marker along with the time, unless paused.
+    fn update_pace(&mut self) -> Result<()> {
+        if self.session.paused {
+            return Ok(());
+        }
+        let position = self.session.pace_position().map(|p| p as usize);
+        self.session.text_lines.set_pace(&mut self.screen, position)
+    }
+
+    /// Show the beat of the metronome once it is reached.
+    fn beat(&mut self) -> Result<()> {
+        let Some(metronome) = &mut self.session.metronome else {
+            return Ok(());
+        };
+        let Some(beat) = metronome.tick(Instant::now()) else {
+            return Ok(());
+        };
+        let bell = metronome.bell;
+        let marks: Vec<&str> = (0..metronome::BAR)
+            .map(|i| match (i == beat % metronome::BAR, i) {
+                (true, 0) => "●",
+                (true, _) => "•",
+                (false, _) => "·",
+            })
+            .collect();
+        let marks = marks.join(" ");
+        self.screen.save()?;
+        self.screen.move_to(
+            self.screen
+                .width
+                .saturating_sub(marks.chars().count() as u16)
+                / 2,
+            1,
+        )?;
+        self.screen.put(marks.as_str().bold())?;
+        if bell {
+            self.screen.bell()?;
         }
+        self.screen.load()?;
+        self.screen.flush()
     }
 
-    pub fn reset(&mut self, text: &str, align_center: bool) -> Result<()> {
-        enable_raw_mode()?;
-        let expect = self
-            .text_lines
-            .reset(Some(text), self.screen.width, align_center);
-        self.text_lines.redraw(&mut self.screen)?;
-        self.layout.redraw(&mut self.screen, expect)?;
-        Ok(())
+    /// Whether the time limit is used up, in which case the timer is stopped right at the limit
+    /// and the screen cleared.
+    fn time_up(&mut self) -> Result<bool> {
+        let Some(limit) = self.session.time_limit else {
+            return Ok(false);
+        };
+        let elapsed = self.session.timer.elapsed();
+        if elapsed < limit {
+            return Ok(false);
+        }
+        // the limit may be noticed a little late, which must not count as typing time
+        let now = Instant::now();
+        let reached = now.checked_sub(elapsed - limit).unwrap_or(now);
+        self.session.timer.pause(reached);
+        self.screen.clear()?;
+        Ok(true)
     }
 
-    pub fn start(&mut self) -> Result<()> {
+    pub fn start(&mut self) -> Result<Exit> {
         loop {
+            if self.time_up()? {
+                return Ok(Exit::Finish);
+            }
+            self.beat()?;
+            if self.until_hesitation() == Some(Duration::ZERO) {
+                let expect = self.session.text_lines.expect();
+                self.hint(expect)?;
+            }
+            // wake up in time for the end, the next beat or hint
+            let mut timeout = TICK;
+            if let Some(remaining) = self.session.remaining() {
+                timeout = timeout.min(remaining);
+            }
+            if let Some(metronome) = &self.session.metronome {
+                timeout = timeout.min(metronome.until_next_beat(Instant::now()));
+            }
+            if let Some(hesitation) = self.until_hesitation() {
+                timeout = timeout.min(hesitation);
+            }
+            if !poll(timeout)? {
+                // keep the timer, speed and pace moving even when no key is pressed
+                self.update_pace()?;
+                self.screen.status(&self.session.status())?;
+                continue;
+            }
+            // a key pressed after the limit is not typed anymore
+            if self.time_up()? {
+                return Ok(Exit::Finish);
+            }
+
             match read()? {
                 Event::Key(event) => {
-                    if event.modifiers == KeyModifiers::CONTROL && event.code == KeyCode::Char('c')
-                    {
-                        self.screen.clear()?;
-                        break;
+                    if let Some(exit) = self.handle_key(event)? {
+                        return Ok(exit);
                     }
-
-                    if event.code == KeyCode::Backspace {
-                        // step 1. update text lines
-                        // move backward
-                        let (expect, redraw) = self.text_lines.backward();
-
-                        // step 2. update screen
-                        // move the cursor on screen
-                        if redraw {
-                            self.text_lines.redraw(&mut self.screen)?;
-                        } else {
-                            self.text_lines.move_to_cursor(&mut self.screen)?;
-                        }
-                        // reset style for current char
-                        let current_char = self.text_lines.current() as char;
-                        self.screen.set(current_char.blank())?;
-
-                        // step 3. inspect next char
-                        self.layout.redraw(&mut self.screen, expect)?;
-
-                        continue;
+                    self.update_pace()?;
+                    self.screen.status(&self.session.status())?;
+                }
+                Event::Resize(width, height) => {
+                    self.screen.set_size(width, height);
+                    let expect = self.session.text_lines.reset(
+                        None,
+                        width,
+                        self.session.text_lines.align_center,
+                    );
+                    if self.session.paused {
+                        self.session.text_lines.redraw_dimmed(&mut self.screen)?;
+                        self.hint(Expect::Paused)?;
+                        self.screen.status(&self.session.status())?;
+                    } else {
+                        self.redraw(expect)?;
                     }
+                }
+                Event::FocusGained | Event::FocusLost | Event::Mouse(_) => (),
+            }
+        }
+    }
 
-                    let c = match event.code {
-                        KeyCode::Enter => Some('\n'),
-                        KeyCode::Char(c) => Some(c),
-                        _ => None,
-                    };
-
-                    if let Some(c) = c {
-                        // step 1. update text lines
-                        // record current char
-                        let current_char = self.text_lines.current() as char;
-                        // move forward
-                        let (state, expect, redraw) = self.text_lines.forward(c);
-
-                        // step 2. update screen
-                        // set style for current char
-                        match state {
-                            State::Hit | State::End => {
-                                self.screen.set(current_char.hit())?;
-                            }
-                            State::Miss => {
-                                self.screen.set(current_char.miss())?;
-                            }
-                        };
-                        // actually move the cursor on screen
-                        if redraw {
-                            self.text_lines.redraw(&mut self.screen)?;
-                        } else {
-                            self.text_lines.move_to_cursor(&mut self.screen)?;
-                        }
-
-                        // step 3. inspect next char
-                        self.layout.redraw(&mut self.screen, expect)?;
-
-                        if matches!(state, State::End) {
-                            self.screen.clear()?;
-                            break;
-                        }
+    /// Show the results of a finished session, and wait for what to do next.
+    pub fn results(&mut self, lines: &[String]) -> Result<Exit> {
+        let can_drill = !self.session.problem_words().is_empty();
+        self.draw_results(lines, can_drill)?;
+        loop {
+            match read()? {
+                Event::Key(event) if event.kind != KeyEventKind::Release => {
+                    let control = event.modifiers == KeyModifiers::CONTROL;
+                    match event.code {
+                        KeyCode::Enter => return Ok(Exit::Next),
+                        KeyCode::Char('n') if control => return Ok(Exit::Next),
+                        KeyCode::Tab => return Ok(Exit::Retry),
+                        KeyCode::Char('r') if control => return Ok(Exit::Retry),
+                        KeyCode::Char('d') if can_drill => return Ok(Exit::Drill),
+                        KeyCode::Esc | KeyCode::Char('q') => return Ok(Exit::Quit),
+                        KeyCode::Char('c') if control => return Ok(Exit::Quit),
+                        _ => (),
                     }
                 }
                 Event::Resize(width, height) => {
                     self.screen.set_size(width, height);
-                    let expect = self
-                        .text_lines
-                        .reset(None, width, self.text_lines.align_center);
-                    self.text_lines.redraw(&mut self.screen)?;
-                    self.layout.redraw(&mut self.screen, expect)?;
+                    self.draw_results(lines, can_drill)?;
                 }
-                Event::FocusGained | Event::FocusLost | Event::Mouse(_) => (),
+                _ => (),
+            }
+        }
+    }
+
+    fn draw_results(&mut self, lines: &[String], can_drill: bool) -> Result<()> {
+        self.screen.clear()?;
+        let top = self.screen.height.saturating_sub(lines.len() as u16 + 2) / 2;
+        let put_line = |screen: &mut MainScreen, row: u16, line: StyledContent<&str>| {
+            let x = screen
+                .width
+                .saturating_sub(line.content().chars().count() as u16)
+                / 2;
+            screen.move_to(x, top + row)?;
+            screen.put(line)
+        };
+        for (i, line) in lines.iter().enumerate() {
+            let line = if i == 0 {
+                line.as_str().bold()
+            } else {
+                line.as_str().reset()
+            };
+            put_line(&mut self.screen, i as u16, line)?;
+        }
+        let hint = if can_drill {
+            "Enter: new text | Tab: retry | d: drill problem words | Esc: quit"
+        } else {
+            "Enter: new text | Tab: retry | Esc: quit"
+        };
+        put_line(&mut self.screen, lines.len() as u16 + 1, hint.dark_grey())?;
+        self.screen.flush()
+    }
+
+    fn handle_key(&mut self, event: KeyEvent) -> Result<Option<Exit>> {
+        let now = Instant::now();
+        let key = match event.code {
+            KeyCode::Enter => Some('\n'),
+            KeyCode::Char(c) => Some(c),
+            KeyCode::Backspace => Some('\x08'),
+            _ => None,
+        };
+        if event.kind == KeyEventKind::Release {
+            if let Some(key) = key {
+                self.session.stat.release(key, now);
+            }
+            return Ok(None);
+        }
+
+        self.last_key = now;
+
+        // control keys
+        let tab_pending = std::mem::take(&mut self.tab_pending);
+        if event.modifiers == KeyModifiers::CONTROL {
+            match event.code {
+                KeyCode::Char('c') => {
+                    self.screen.clear()?;
+                    return Ok(Some(Exit::Quit));
+                }
+                KeyCode::Char('r') => {
+                    self.restart()?;
+                    return Ok(None);
+                }
+                KeyCode::Char('n') => {
+                    return Ok(Some(Exit::Next));
+                }
+                _ => (),
+            }
+        }
+        match event.code {
+            KeyCode::Tab => {
+                self.tab_pending = true;
+                return Ok(None);
+            }
+            KeyCode::Enter if tab_pending => {
+                self.restart()?;
+                return Ok(None);
+            }
+            KeyCode::Esc if !self.session.paused => {
+                self.session.pause(now);
+                self.session.text_lines.redraw_dimmed(&mut self.screen)?;
+                self.hint(Expect::Paused)?;
+                return Ok(None);
+            }
+            _ => (),
+        }
+
+        // any key resumes a paused session, without being typed
+        if self.session.paused {
+            self.session.resume();
+            let expect = self.session.text_lines.expect();
+            self.redraw(expect)?;
+            return Ok(None);
+        }
+
+        if let Some(key) = key {
+            self.session.timer.start(now);
+            self.session.stat.press(key, now);
+        }
+
+        let text_lines = &mut self.session.text_lines;
+        if event.code == KeyCode::Backspace {
+            // step 1. update text lines
+            // move backward
+            let (expect, redraw) = text_lines.backward();
+
+            // step 2. update screen
+            // move the cursor on screen
+            if redraw {
+                text_lines.redraw(&mut self.screen)?;
+            } else {
+                text_lines.move_to_cursor(&mut self.screen)?;
+            }
+            // reset style for current char
+            let current_char = text_lines.current();
+            self.screen.set(current_char.blank())?;
+
+            // step 3. inspect next char
+            self.hint(expect)?;
+
+            return Ok(None);
+        }
+
+        let c = match event.code {
+            KeyCode::Enter => Some('\n'),
+            KeyCode::Char(c) => Some(c),
+            _ => None,
+        };
+
+        if let Some(c) = c {
+            // step 1. update text lines
+            // record current char
+            let current_char = text_lines.current();
+            // the char expected, misses are recorded there as well
+            let position = text_lines.n_hit;
+            // move forward
+            let (state, expect, redraw) = text_lines.forward(c);
+            let hit = !matches!(state, State::Miss);
+            let time = self.session.timer.elapsed();
+            self.session.stat.record(position, c, hit, time);
+            text_lines.record(position, hit, time);
+            // misses are kept secret in blind mode
+            self.missed = !hit && !text_lines.blind;
+
+            // step 2. update screen
+            // set style for current char
+            match state {
+                _ if text_lines.blind => {
+                    self.screen.set(current_char.default())?;
+                }
+                State::Hit | State::End => {
+                    self.screen.set(current_char.hit())?;
+                }
+                State::Miss => {
+                    self.screen.set(current_char.miss())?;
+                }
+            };
+            // actually move the cursor on screen
+            if redraw {
+                text_lines.redraw(&mut self.screen)?;
+            } else {
+                text_lines.move_to_cursor(&mut self.screen)?;
+            }
+
+            // step 3. inspect next char
+            self.hint(expect)?;
+
+            if self.session.check_challenge(false) {
+                self.session.timer.pause(now);
+                self.screen.clear()?;
+                return Ok(Some(Exit::Fail));
+            }
+            // a blind miss on the last char ends the text as well
+            if self.session.text_lines.is_finished() {
+                self.session.timer.pause(now);
+                self.screen.clear()?;
+                return Ok(Some(Exit::Finish));
             }
         }
 
-        Ok(())
+        Ok(None)
     }
 }
 
@@ -0,0 +1,91 @@
+use std::str::FromStr;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::session::PracticeText;
+
+const QUOTES: &str = include_str!("quotes.txt");
+
+/// Length class of a quote, by number of chars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Length {
+    /// Less than 100 chars.
+    Short,
+    /// 100 to 249 chars.
+    Medium,
+    /// 250 chars or more.
+    Long,
+}
+
+impl FromStr for Length {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "short" => Ok(Length::Short),
+            "medium" => Ok(Length::Medium),
+            "long" => Ok(Length::Long),
+            _ => Err(format!("`{s}` is not one of short, medium or long")),
+        }
+    }
+}
+
+/// A quote or short passage from the bundled corpus.
+#[derive(Debug, Clone)]
+pub struct Quote {
+    pub id: usize,
+    pub author: &'static str,
+    pub text: &'static str,
+}
+
+impl Quote {
+    #[must_use]
+    pub fn length(&self) -> Length {
+        match self.text.chars().count() {
+            0..=99 => Length::Short,
+            100..=249 => Length::Medium,
+            _ => Length::Long,
+        }
+    }
+
+    #[must_use]
+    pub fn practice(&self) -> PracticeText {
+        PracticeText {
+            text: self.text.to_string(),
+            align_center: true,
+            source: format!("quote #{} by {}", self.id, self.author),
+            seed: None,
+        }
+    }
+}
+
+/// All quotes of the corpus, with ids starting from 1.
+#[must_use]
+pub fn quotes() -> Vec<Quote> {
+    QUOTES
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .filter_map(|line| line.split_once('\t'))
+        .enumerate()
+        .map(|(i, (author, text))| Quote {
+            id: i + 1,
+            author,
+            text,
+        })
+        .collect()
+}
+
+/// A quote by its id.
+#[must_use]
+pub fn get(id: usize) -> Option<Quote> {
+    quotes().into_iter().find(|quote| quote.id == id)
+}
+
+/// A random quote, of the given length class if any.
+pub fn pick<R: Rng>(length: Option<Length>, rng: &mut R) -> Option<Quote> {
+    let quotes: Vec<Quote> = quotes()
+        .into_iter()
+        .filter(|quote| length.is_none_or(|length| quote.length() == length))
+        .collect();
+    quotes.choose(rng).cloned()
+}
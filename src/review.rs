@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::Result;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{storage::Table, text::TextLines, wordlist::WordList};
+
+/// Quality of a review, from 0 (forgotten) to 5 (perfect), as in SM-2.
+pub type Quality = u8;
+
+/// A mistyped word.
+const MISTYPED: Quality = 1;
+/// A word typed correctly but among the slowest.
+const SLOW: Quality = 3;
+/// A word typed correctly at a normal speed.
+const CLEAN: Quality = 5;
+
+/// Share of due words in a review session, the rest are new words.
+const DUE_SHARE: f64 = 0.7;
+
+/// A problem word, scheduled for review.
+#[derive(Debug, Clone)]
+pub struct Card {
+    pub word: String,
+    /// Reviews in a row that passed.
+    pub repetitions: u32,
+    /// Days until the next review.
+    pub interval: u32,
+    pub ease: f64,
+    /// Day of the next review, in days since the unix epoch.
+    pub due: i64,
+    /// How often the word was mistyped after being learned.
+    pub lapses: u32,
+}
+
+impl Card {
+    #[must_use]
+    pub fn new(word: &str, today: i64) -> Self {
+        Self {
+            word: word.to_string(),
+            repetitions: 0,
+            interval: 0,
+            ease: 2.5,
+            due: today,
+            lapses: 0,
+        }
+    }
+
+    /// Schedule the next review after one of the given quality, following SM-2.
+    pub fn review(&mut self, quality: Quality, today: i64) {
+        let q = f64::from(quality.min(5));
+        if quality < 3 {
+            if self.repetitions > 0 {
+                self.lapses += 1;
+            }
+            self.repetitions = 0;
+            self.interval = 1;
+        } else {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (f64::from(self.interval) * self.ease).round() as u32,
+            };
+            self.repetitions += 1;
+        }
+        self.ease = (self.ease + 0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02)).max(1.3);
+        self.due = today + i64::from(self.interval);
+    }
+}
+
+/// Problem words of all sessions, kept between runs.
+#[derive(Debug, Default)]
+pub struct Deck {
+    pub cards: BTreeMap<String, Card>,
+}
+
+/// The word in a token of the text without the punctuation around it, unless it is not a
+/// plain word, like most tokens of code.
+fn core(token: &str) -> Option<String> {
+    let core = token.trim_matches(|c: char| !c.is_alphanumeric());
+    let is_word = !core.is_empty()
+        && core
+            .chars()
+            .all(|c| c.is_alphabetic() || c == '\'' || c == '-');
+    is_word.then(|| core.to_string())
+}
+
+/// The review deck, rewritten after every session.
+const TABLE: Table = Table {
+    file: "review.tsv",
+    kind: "review",
+    version: 1,
+    columns: &["word", "repetitions", "interval", "ease", "due", "lapses"],
+};
+
+impl Deck {
+    pub fn load() -> Result<Self> {
+        let mut deck = Self::default();
+        for row in TABLE.load()?.rows {
+            if let [word, repetitions, interval, ease, due, lapses] = &row[..] {
+                if let (Ok(repetitions), Ok(interval), Ok(ease), Ok(due), Ok(lapses)) = (
+                    repetitions.parse(),
+                    interval.parse(),
+                    ease.parse(),
+                    due.parse(),
+                    lapses.parse(),
+                ) {
+                    if core(word).as_ref() != Some(word) {
+                        // left over from before code tokens were kept out
+                        continue;
+                    }
+                    let card = Card {
+                        word: word.clone(),
+                        repetitions,
+                        interval,
+                        ease,
+                        due,
+                        lapses,
+                    };
+                    deck.cards.insert(card.word.clone(), card);
+                }
+            }
+        }
+        Ok(deck)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let rows: Vec<Vec<String>> = self
+            .cards
+            .values()
+            .map(|card| {
+                vec![
+                    card.word.clone(),
+                    card.repetitions.to_string(),
+                    card.interval.to_string(),
+                    format!("{:.2}", card.ease),
+                    card.due.to_string(),
+                    card.lapses.to_string(),
+                ]
+            })
+            .collect();
+        TABLE.save(&rows)
+    }
+
+    /// Grade the words of a finished text.
+    ///
+    /// Problem words join the deck, and the cards of the deck that were due are reviewed.
+    /// A mistyped card is reviewed even before it is due, since it was clearly not learned.
+    pub fn update(&mut self, text_lines: &TextLines, problem_words: &[String], today: i64) {
+        let mut graded = HashSet::new();
+        for word in text_lines.words() {
+            let raw = text_lines.word_text(word);
+            let Some(text) = core(&raw) else {
+                continue;
+            };
+            if !graded.insert(text.clone()) {
+                continue;
+            }
+            let quality = if word.n_miss > 0 {
+                MISTYPED
+            } else if problem_words.contains(&raw) {
+                SLOW
+            } else {
+                CLEAN
+            };
+            match self.cards.get_mut(&text) {
+                Some(card) if card.due <= today || quality == MISTYPED => {
+                    card.review(quality, today);
+                }
+                Some(_) => (),
+                None if quality < CLEAN => {
+                    let mut card = Card::new(&text, today);
+                    card.review(quality, today);
+                    self.cards.insert(text, card);
+                }
+                None => (),
+            }
+        }
+    }
+
+    /// Cards due today or earlier, the most overdue first.
+    #[must_use]
+    pub fn due(&self, today: i64) -> Vec<&Card> {
+        let mut cards: Vec<&Card> = self.cards.values().filter(|c| c.due <= today).collect();
+        cards.sort_by_key(|card| card.due);
+        cards
+    }
+
+    /// A review text of `n` words, due words first, topped up with new words from `words`.
+    pub fn drill<R: Rng>(&self, words: &WordList, n: usize, today: i64, rng: &mut R) -> String {
+        let n_due = (n as f64 * DUE_SHARE).round() as usize;
+        let mut text: Vec<&str> = self
+            .due(today)
+            .into_iter()
+            .take(n_due)
+            .map(|card| card.word.as_str())
+            .collect();
+        let fresh: Vec<&String> = words
+            .words
+            .iter()
+            .filter(|word| !self.cards.contains_key(*word))
+            .collect();
+        text.extend(
+            fresh
+                .choose_multiple(rng, n - text.len())
+                .map(|word| word.as_str()),
+        );
+        text.shuffle(rng);
+        text.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_ease(card: &Card, ease: f64) {
+        assert!((card.ease - ease).abs() < 1e-9, "{} != {ease}", card.ease);
+    }
+
+    #[test]
+    fn intervals_grow_with_clean_reviews() {
+        let mut card = Card::new("word", 100);
+        card.review(CLEAN, 100);
+        assert_eq!((card.repetitions, card.interval, card.due), (1, 1, 101));
+        assert_ease(&card, 2.6);
+        card.review(CLEAN, 101);
+        assert_eq!((card.repetitions, card.interval, card.due), (2, 6, 107));
+        assert_ease(&card, 2.7);
+        card.review(CLEAN, 107);
+        // 6 days times the ease of 2.7, rounded
+        assert_eq!((card.repetitions, card.interval, card.due), (3, 16, 123));
+        assert_ease(&card, 2.8);
+    }
+
+    #[test]
+    fn slow_reviews_lower_the_ease() {
+        let mut card = Card::new("word", 0);
+        card.review(SLOW, 0);
+        assert_eq!(card.interval, 1);
+        assert_ease(&card, 2.36);
+    }
+
+    #[test]
+    fn misses_start_over() {
+        let mut card = Card::new("word", 0);
+        card.review(CLEAN, 0);
+        card.review(CLEAN, 1);
+        card.review(MISTYPED, 7);
+        assert_eq!((card.repetitions, card.interval, card.due), (0, 1, 8));
+        assert_eq!(card.lapses, 1);
+        assert_ease(&card, 2.7 - 0.54);
+        // a card that was never learned does not lapse
+        let mut card = Card::new("word", 0);
+        card.review(MISTYPED, 0);
+        assert_eq!(card.lapses, 0);
+    }
+
+    #[test]
+    fn ease_has_a_floor() {
+        let mut card = Card::new("word", 0);
+        for day in 0..10 {
+            card.review(0, day);
+        }
+        assert_ease(&card, 1.3);
+    }
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use crate::{chart, stat::Stat};
+
+/// Correct chars in a row over which the burst speed is measured.
+const BURST_CHARS: usize = 10;
+
+/// Widest sparkline on the results screen, longer sessions are squeezed.
+const SPARKLINE_WIDTH: usize = 60;
+
+/// How steady the typing was over a session.
+#[derive(Debug, Clone, Default)]
+pub struct Rhythm {
+    /// Speed during each second of the session, in words per minute.
+    pub samples: Vec<f64>,
+    /// Standard deviation of the intervals between correct chars over their mean.
+    pub variation: f64,
+    /// Best speed over `BURST_CHARS` correct chars in a row.
+    pub burst_wpm: f64,
+    /// Longest interval between correct chars.
+    pub longest_pause: Duration,
+    /// Average time a key is held down, if the terminal reports releases.
+    pub mean_dwell: Option<Duration>,
+    /// Average time from releasing a key to pressing the next one, if the terminal reports
+    /// releases.
+    pub mean_flight: Option<Duration>,
+}
+
+impl Rhythm {
+    #[must_use]
+    pub fn new(stat: &Stat, elapsed: Duration) -> Self {
+        let times: Vec<Duration> = stat.hits.iter().map(|&(_, time)| time).collect();
+
+        let mut counts = vec![0usize; elapsed.as_secs() as usize + 1];
+        for time in &times {
+            if let Some(count) = counts.get_mut(time.as_secs() as usize) {
+                *count += 1;
+            }
+        }
+        // a word is five chars, a second a sixtieth of a minute
+        let samples = counts.iter().map(|&n| n as f64 * 12.0).collect();
+
+        let intervals: Vec<f64> = times
+            .windows(2)
+            .map(|w| w[1].saturating_sub(w[0]).as_secs_f64())
+            .collect();
+        let n = intervals.len() as f64;
+        let mean = intervals.iter().sum::<f64>() / n;
+        let variance = intervals.iter().map(|i| (i - mean).powi(2)).sum::<f64>() / n;
+        let variation = if intervals.is_empty() || mean == 0.0 {
+            0.0
+        } else {
+            variance.sqrt() / mean
+        };
+
+        let burst_wpm = times
+            .windows(BURST_CHARS + 1)
+            .map(|w| {
+                let minutes = w[BURST_CHARS].saturating_sub(w[0]).as_secs_f64() / 60.0;
+                if minutes > 0.0 {
+                    BURST_CHARS as f64 / 5.0 / minutes
+                } else {
+                    0.0
+                }
+            })
+            .fold(0.0, f64::max);
+
+        let longest_pause = times
+            .windows(2)
+            .map(|w| w[1].saturating_sub(w[0]))
+            .max()
+            .unwrap_or_default();
+
+        Self {
+            samples,
+            variation,
+            burst_wpm,
+            longest_pause,
+            mean_dwell: mean_duration(stat.dwell_times().map(|(_, d)| d)),
+            mean_flight: mean_duration(stat.flight_times().map(|(_, d)| d)),
+        }
+    }
+
+    /// Lines for the results screen.
+    #[must_use]
+    pub fn summary(&self) -> Vec<String> {
+        let mut line = format!(
+            "burst {:.0} wpm | longest pause {:.1}s | variation {:.0}%",
+            self.burst_wpm,
+            self.longest_pause.as_secs_f64(),
+            self.variation * 100.0
+        );
+        if let (Some(dwell), Some(flight)) = (self.mean_dwell, self.mean_flight) {
+            line.push_str(&format!(
+                " | dwell {}ms, flight {}ms",
+                dwell.as_millis(),
+                flight.as_millis()
+            ));
+        }
+        vec![line, chart::sparkline(&self.samples, SPARKLINE_WIDTH)]
+    }
+}
+
+fn mean_duration(durations: impl Iterator<Item = Duration>) -> Option<Duration> {
+    let (sum, n) = durations.fold((Duration::ZERO, 0), |(sum, n), d| (sum + d, n + 1));
+    sum.checked_div(n)
+}
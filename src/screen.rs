@@ -2,7 +2,7 @@ use std::io::{stdout, Result, Stdout, Write};
 
 use crossterm::{
     cursor, execute, queue,
-    style::{PrintStyledContent, StyledContent, Stylize},
+    style::{Print, PrintStyledContent, StyledContent, Stylize},
     terminal::{size, Clear, ClearType},
 };
 
@@ -67,12 +67,15 @@ impl MainScreen {
         queue!(self.stdout, cursor::RestorePosition)
     }
 
-    pub fn debug(&mut self, s: &str) -> Result<()> {
+    /// Draw a status line at the bottom of the screen.
+    pub fn status(&mut self, s: &str) -> Result<()> {
+        let width = (self.width as usize).saturating_sub(2);
+        let s: String = s.chars().take(width).collect();
         queue!(
             self.stdout,
             cursor::SavePosition,
             cursor::MoveTo(1, self.height - 1),
-            PrintStyledContent(" ".repeat(self.width as usize - 2).underlined()),
+            PrintStyledContent(" ".repeat(width).underlined()),
             cursor::MoveTo(1, self.height - 1),
             PrintStyledContent(s.bold().underlined()),
             cursor::RestorePosition,
@@ -80,6 +83,11 @@ impl MainScreen {
         self.flush()
     }
 
+    /// Ring the terminal bell.
+    pub fn bell(&mut self) -> Result<()> {
+        queue!(self.stdout, Print('\x07'))
+    }
+
     pub fn flush(&mut self) -> Result<()> {
         self.stdout.flush()
     }
@@ -104,6 +112,12 @@ pub trait Styled: Sized {
     fn blank(self) -> Self::Formatted {
         self.format().dark_grey()
     }
+    fn pace(self) -> Self::Formatted {
+        self.format().black().on_dark_yellow()
+    }
+    fn dimmed(self) -> Self::Formatted {
+        self.format().dark_grey().dim()
+    }
     fn default(self) -> Self::Formatted {
         self.format().reset()
     }
@@ -0,0 +1,211 @@
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::{
+    challenge::Challenge,
+    date,
+    history::{History, Record},
+    metronome::Metronome,
+    stat::{format_duration, Stat, Timer},
+    text::{Expect, TextLines},
+};
+
+/// A text to practice, along with where it came from.
+#[derive(Debug, Clone, Default)]
+pub struct PracticeText {
+    pub text: String,
+    pub align_center: bool,
+    /// Where the text came from, e.g. `words:english` or a file path.
+    pub source: String,
+    /// Seed the text was generated with, so that it can be generated again.
+    pub seed: Option<u64>,
+}
+
+/// Everything that belongs to a single run over a text, and is thrown away on restart.
+#[derive(Default)]
+pub struct Session {
+    pub practice: PracticeText,
+    pub text_lines: TextLines,
+    pub stat: Stat,
+    pub timer: Timer,
+    pub paused: bool,
+    /// Optional time limit, the session ends once it is used up.
+    pub time_limit: Option<Duration>,
+    /// Optional target speed in words per minute, shown as a marker moving through the text.
+    pub pace: Option<f64>,
+    /// Optional beat to type along with.
+    pub metronome: Option<Metronome>,
+    /// Optional challenge to keep to.
+    pub challenge: Option<Challenge>,
+    /// Why the challenge was failed, if it was.
+    pub failure: Option<String>,
+}
+
+/// How the target speed of the pace marker is set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pace {
+    Wpm(f64),
+    /// A bit faster than the average of the last sessions.
+    Auto,
+}
+
+impl FromStr for Pace {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Pace::Auto),
+            _ => match s.parse::<f64>() {
+                Ok(wpm) if wpm > 0.0 => Ok(Pace::Wpm(wpm)),
+                _ => Err(format!("`{s}` is neither a speed in wpm nor auto")),
+            },
+        }
+    }
+}
+
+/// Sessions the automatic pace is averaged over.
+const PACE_SESSIONS: usize = 10;
+
+/// How much faster than the recent average the automatic pace is.
+const PACE_MARGIN: f64 = 1.05;
+
+/// Pace when there is no history to go by yet.
+const DEFAULT_PACE: f64 = 30.0;
+
+impl Pace {
+    /// Target speed in words per minute, given the sessions so far.
+    #[must_use]
+    pub fn target(self, history: &History) -> f64 {
+        match self {
+            Pace::Wpm(wpm) => wpm,
+            Pace::Auto => {
+                let recent =
+                    &history.records[history.records.len().saturating_sub(PACE_SESSIONS)..];
+                if recent.is_empty() {
+                    DEFAULT_PACE
+                } else {
+                    let average = recent.iter().map(|r| r.wpm).sum::<f64>() / recent.len() as f64;
+                    (average * PACE_MARGIN).max(1.0)
+                }
+            }
+        }
+    }
+}
+
+/// Window over which the live typing speed is measured.
+const WPM_WINDOW: Duration = Duration::from_secs(10);
+
+/// Most words in a follow-up drill of problem words.
+const PROBLEM_WORDS: usize = 10;
+
+impl Session {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self, practice: PracticeText, width: u16) -> Expect {
+        self.practice = practice;
+        self.restart(width)
+    }
+
+    /// Start over on the same text.
+    pub fn restart(&mut self, width: u16) -> Expect {
+        self.stat.reset();
+        self.timer.reset();
+        self.paused = false;
+        self.failure = None;
+        if let Some(metronome) = &mut self.metronome {
+            metronome.reset(Instant::now());
+        }
+        self.text_lines
+            .reset(Some(&self.practice.text), width, self.practice.align_center)
+    }
+
+    pub fn pause(&mut self, time: Instant) {
+        self.timer.pause(time);
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        // the timer starts again with the next key stroke
+        self.paused = false;
+    }
+
+    /// Time left before the time limit is reached.
+    #[must_use]
+    pub fn remaining(&self) -> Option<Duration> {
+        self.time_limit
+            .map(|limit| limit.saturating_sub(self.timer.elapsed()))
+    }
+
+    /// Chars the pace marker has moved through the text, a word being five chars.
+    #[must_use]
+    pub fn pace_position(&self) -> Option<f64> {
+        self.pace
+            .map(|wpm| wpm * 5.0 / 60.0 * self.timer.elapsed().as_secs_f64())
+    }
+
+    /// Text for the status bar.
+    #[must_use]
+    pub fn status(&self) -> String {
+        let elapsed = self.timer.elapsed();
+        let time = match self.remaining() {
+            Some(remaining) => format!("{} left", format_duration(remaining)),
+            None => format_duration(elapsed),
+        };
+        let pace = match (self.pace, self.pace_position()) {
+            (Some(wpm), Some(position)) => {
+                // how long the pace marker takes to cover the gap
+                let gap = (self.text_lines.n_hit as f64 - position) / (wpm * 5.0 / 60.0);
+                let side = if gap < 0.0 { "behind" } else { "ahead of" };
+                format!(" | {:.1}s {side} {wpm:.0} wpm", gap.abs())
+            }
+            _ => String::new(),
+        };
+        format!(
+            "{}{:.0} wpm | {:.1}% acc | {:.0}% done | {} | streak {}{}",
+            if self.paused { "paused | " } else { "" },
+            self.stat.rolling_wpm(elapsed, WPM_WINDOW),
+            self.stat.accuracy(),
+            self.text_lines.progress(),
+            time,
+            self.stat.streak,
+            pace,
+        )
+    }
+
+    /// Check the challenge, either while typing or once `finished`, and tell whether it failed.
+    pub fn check_challenge(&mut self, finished: bool) -> bool {
+        self.failure = self
+            .challenge
+            .and_then(|challenge| challenge.check(&self.stat, self.timer.elapsed(), finished));
+        self.failure.is_some()
+    }
+
+    /// Words that were mistyped or typed slowest, to be drilled again.
+    #[must_use]
+    pub fn problem_words(&self) -> Vec<String> {
+        self.text_lines.problem_words(PROBLEM_WORDS)
+    }
+
+    /// Results of the session, to be kept in the history.
+    #[must_use]
+    pub fn record(&self) -> Record {
+        let elapsed = self.timer.elapsed();
+        Record {
+            id: 0,
+            time: date::now(),
+            source: self.practice.source.clone(),
+            seed: self.practice.seed,
+            text: self.practice.text.clone(),
+            align_center: self.practice.align_center,
+            elapsed: elapsed.as_secs_f64(),
+            wpm: self.stat.wpm(elapsed),
+            accuracy: self.stat.accuracy(),
+            n_correct: self.stat.n_correct,
+            n_wrong: self.stat.n_wrong,
+            best_streak: self.stat.best_streak,
+        }
+    }
+}
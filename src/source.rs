@@ -0,0 +1,129 @@
+use std::fs::{self, File};
+use std::io::{stdin, Error, ErrorKind, Read, Result};
+use std::path::{Path, PathBuf};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{excerpt::Excerpt, session::PracticeText};
+
+/// Practice text read from files, directories or stdin.
+pub struct TextSource {
+    files: Vec<PathBuf>,
+    stdin: Option<String>,
+    excerpt: Excerpt,
+}
+
+impl TextSource {
+    /// Collect the given paths, where `-` stands for stdin and directories are walked recursively.
+    ///
+    /// Each pick is cut down to `excerpt`, which is usually the whole text.
+    pub fn new(paths: &[String], excerpt: Excerpt) -> Result<Self> {
+        let mut files = Vec::new();
+        let mut stdin_text = None;
+        for path in paths {
+            if path == "-" {
+                // stdin can only be consumed once, so keep it around for new texts
+                if stdin_text.is_none() {
+                    let mut text = String::new();
+                    stdin().read_to_string(&mut text)?;
+                    stdin_text = Some(text);
+                }
+            } else {
+                collect_files(Path::new(path), &mut files)?;
+            }
+        }
+        files.sort();
+        files.dedup();
+        if files.is_empty() && stdin_text.is_none() {
+            return Err(Error::new(ErrorKind::NotFound, "no text files found"));
+        }
+        Ok(Self {
+            files,
+            stdin: stdin_text,
+            excerpt,
+        })
+    }
+
+    /// Pick a text at random.
+    pub fn pick<R: Rng>(&self, rng: &mut R) -> Result<PracticeText> {
+        let mut candidates: Vec<Option<&PathBuf>> = self.files.iter().map(Some).collect();
+        if self.stdin.is_some() {
+            candidates.push(None);
+        }
+        candidates.shuffle(rng);
+
+        for candidate in candidates {
+            let (text, align_center, source) = match candidate {
+                Some(path) => match read_file(path)? {
+                    Some(text) => (text, is_prose(path), path.display().to_string()),
+                    None => continue,
+                },
+                // like a file without the `.txt` extension, so a long paste starts at the top
+                None => (
+                    self.stdin.clone().unwrap_or_default(),
+                    false,
+                    "stdin".into(),
+                ),
+            };
+            if let Some(text) = self.excerpt.cut(&text, rng) {
+                return Ok(PracticeText {
+                    text,
+                    align_center,
+                    source,
+                    seed: None,
+                });
+            }
+        }
+        Err(Error::new(ErrorKind::InvalidData, "no usable text found"))
+    }
+}
+
+/// Directories of build output and dependencies, which are not worth typing.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules"];
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    walk_dir(path, files)
+}
+
+/// Collect the files below `dir`, without following symlinks so that a loop cannot hold
+/// up the walk.
+fn walk_dir(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        // skip hidden files and directories such as `.git`
+        if name.starts_with('.') {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_ref()) {
+                walk_dir(&entry.path(), files)?;
+            }
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Read a file, or `None` if it is not utf-8 text.
+fn read_file(path: &Path) -> Result<Option<String>> {
+    let mut text = String::new();
+    match File::open(path)?.read_to_string(&mut text) {
+        Ok(_) => Ok(Some(text)),
+        Err(e) if e.kind() == ErrorKind::InvalidData => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Plain text files are centered, anything else is considered code and kept left aligned.
+#[must_use]
+pub fn is_prose(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "txt")
+}
@@ -1 +1,198 @@
+use std::time::{Duration, Instant};
 
+/// A single key stroke, with its release time if the terminal reports it.
+#[derive(Debug, Clone)]
+pub struct Keystroke {
+    pub key: char,
+    pub pressed: Instant,
+    pub released: Option<Instant>,
+    /// Position in the text of the char that was expected, unless it was a backspace.
+    pub position: Option<usize>,
+    /// Whether the char was correct, unless it was a backspace.
+    pub hit: Option<bool>,
+}
+
+/// Wrong chars typed in a row, from the first mistake until it is corrected.
+#[derive(Debug, Clone)]
+pub struct Typo {
+    /// Position in the text of the char that was expected.
+    pub position: usize,
+    /// Wrong chars, up to the first backspace.
+    pub typed: Vec<char>,
+}
+
+#[derive(Default)]
+pub struct Stat {
+    pub keystrokes: Vec<Keystroke>,
+    /// Positions in the text of correctly typed chars, with their session times.
+    pub hits: Vec<(usize, Duration)>,
+    /// Positions in the text of the chars expected when wrong chars were typed, with their
+    /// session times.
+    pub misses: Vec<(usize, Duration)>,
+    pub n_correct: usize,
+    pub n_wrong: usize,
+    pub streak: usize,
+    pub best_streak: usize,
+    pub typos: Vec<Typo>,
+    /// Whether wrong chars still add to the last typo.
+    typo_open: bool,
+}
+
+impl Stat {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Record `typed` for the char at `position`, whether it was correct, at the given session
+    /// time.
+    pub fn record(&mut self, position: usize, typed: char, hit: bool, time: Duration) {
+        if let Some(stroke) = self.keystrokes.last_mut() {
+            stroke.position = Some(position);
+            stroke.hit = Some(hit);
+        }
+        if hit {
+            self.typo_open = false;
+            self.n_correct += 1;
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+            self.hits.push((position, time));
+        } else {
+            self.n_wrong += 1;
+            self.streak = 0;
+            self.misses.push((position, time));
+            match self.typos.last_mut() {
+                Some(typo) if self.typo_open => typo.typed.push(typed),
+                _ => self.typos.push(Typo {
+                    position,
+                    typed: vec![typed],
+                }),
+            }
+            self.typo_open = true;
+        }
+    }
+
+    /// Ratio of correctly typed chars, in percent.
+    #[must_use]
+    pub fn accuracy(&self) -> f64 {
+        let total = self.n_correct + self.n_wrong;
+        if total == 0 {
+            100.0
+        } else {
+            self.n_correct as f64 * 100.0 / total as f64
+        }
+    }
+
+    /// Words per minute over the whole session, a word being five chars.
+    #[must_use]
+    pub fn wpm(&self, elapsed: Duration) -> f64 {
+        wpm(self.hits.len(), elapsed)
+    }
+
+    /// Words per minute over the last `window` of session time.
+    #[must_use]
+    pub fn rolling_wpm(&self, now: Duration, window: Duration) -> f64 {
+        let since = now.saturating_sub(window);
+        let n = self
+            .hits
+            .iter()
+            .rev()
+            .take_while(|(_, t)| *t >= since)
+            .count();
+        wpm(n, now - since)
+    }
+
+    pub fn press(&mut self, key: char, time: Instant) {
+        if key == '\x08' {
+            self.typo_open = false;
+        }
+        self.keystrokes.push(Keystroke {
+            key,
+            pressed: time,
+            released: None,
+            position: None,
+            hit: None,
+        });
+    }
+
+    pub fn release(&mut self, key: char, time: Instant) {
+        // match the latest press of the same key which is not released yet
+        if let Some(stroke) = self
+            .keystrokes
+            .iter_mut()
+            .rev()
+            .find(|s| s.key == key && s.released.is_none())
+        {
+            stroke.released = Some(time);
+        }
+    }
+
+    /// How long each key is held down, for keys whose release is known.
+    pub fn dwell_times(&self) -> impl Iterator<Item = (char, Duration)> + '_ {
+        self.keystrokes
+            .iter()
+            .filter_map(|s| Some((s.key, s.released?.checked_duration_since(s.pressed)?)))
+    }
+
+    /// Time between releasing a key and pressing the next one, keyed by the next key.
+    pub fn flight_times(&self) -> impl Iterator<Item = (char, Duration)> + '_ {
+        self.keystrokes.windows(2).filter_map(|w| {
+            let released = w[0].released?;
+            Some((w[1].key, w[1].pressed.checked_duration_since(released)?))
+        })
+    }
+}
+
+fn wpm(n_chars: usize, elapsed: Duration) -> f64 {
+    let minutes = elapsed.as_secs_f64() / 60.0;
+    if minutes > 0.0 {
+        n_chars as f64 / 5.0 / minutes
+    } else {
+        0.0
+    }
+}
+
+/// Format a duration as `m:ss`.
+#[must_use]
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Stopwatch for a typing session, which can be paused and resumed.
+#[derive(Default)]
+pub struct Timer {
+    started: Option<Instant>,
+    elapsed: Duration,
+}
+
+impl Timer {
+    pub fn reset(&mut self) {
+        self.started = None;
+        self.elapsed = Duration::ZERO;
+    }
+
+    pub fn start(&mut self, time: Instant) {
+        if self.started.is_none() {
+            self.started = Some(time);
+        }
+    }
+
+    pub fn pause(&mut self, time: Instant) {
+        if let Some(started) = self.started.take() {
+            self.elapsed += time.saturating_duration_since(started);
+        }
+    }
+
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        match self.started {
+            Some(started) => self.elapsed + started.elapsed(),
+            None => self.elapsed,
+        }
+    }
+}
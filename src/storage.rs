@@ -0,0 +1,206 @@
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Error, ErrorKind, Result, Write};
+use std::path::{Path, PathBuf};
+
+/// Directory where pecker keeps its data, created on demand.
+///
+/// `PECKER_HOME` takes precedence, then the platform's usual data directory.
+pub fn data_dir() -> Result<PathBuf> {
+    let dir = if let Some(home) = env::var_os("PECKER_HOME") {
+        PathBuf::from(home)
+    } else if let Some(data) = env::var_os("XDG_DATA_HOME") {
+        PathBuf::from(data).join("pecker")
+    } else if let Some(appdata) = env::var_os("APPDATA") {
+        PathBuf::from(appdata).join("pecker")
+    } else if let Some(home) = env::var_os("HOME") {
+        PathBuf::from(home).join(".local/share/pecker")
+    } else {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            "cannot determine data directory, please set PECKER_HOME",
+        ));
+    };
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// A subdirectory of the data directory, created on demand.
+pub fn data_subdir(name: &str) -> Result<PathBuf> {
+    let dir = data_dir()?.join(name);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// A tab separated file in the data directory, starting with a versioned header such as
+/// `# pecker history v1` and a row of column names.
+#[derive(Debug, Clone, Copy)]
+pub struct Table {
+    /// File name in the data directory.
+    pub file: &'static str,
+    /// What the file holds, as named in the header.
+    pub kind: &'static str,
+    /// Latest version of the format, bumped whenever columns change meaning.
+    pub version: u32,
+    /// Columns of the latest version, in order.
+    pub columns: &'static [&'static str],
+}
+
+/// Rows of a table as read from its file, with the version and columns they were written in.
+#[derive(Debug, Clone, Default)]
+pub struct Rows {
+    pub version: u32,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn path(&self) -> Result<PathBuf> {
+        Ok(data_dir()?.join(self.file))
+    }
+
+    fn header(&self) -> String {
+        format!(
+            "# pecker {} v{}\n{}\n",
+            self.kind,
+            self.version,
+            self.columns.join("\t")
+        )
+    }
+
+    /// All rows of the file, oldest first, or none if there is no file yet.
+    ///
+    /// Older versions are left to the caller to migrate, newer ones are rejected rather than
+    /// misread.
+    pub fn load(&self) -> Result<Rows> {
+        let path = self.path()?;
+        if !path.is_file() {
+            return Ok(Rows {
+                version: self.version,
+                columns: self.columns.iter().map(|c| c.to_string()).collect(),
+                rows: Vec::new(),
+            });
+        }
+        let content = fs::read_to_string(&path)?;
+        let mut lines = content.lines();
+        let version = self.check_version(&path, lines.next())?;
+        let columns = lines
+            .next()
+            .map(|line| line.split('\t').map(str::to_string).collect())
+            .unwrap_or_default();
+        let rows = lines
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.split('\t').map(unescape).collect())
+            .collect();
+        Ok(Rows {
+            version,
+            columns,
+            rows,
+        })
+    }
+
+    fn check_version(&self, path: &Path, first: Option<&str>) -> Result<u32> {
+        let invalid = |msg: String| Error::new(ErrorKind::InvalidData, msg);
+        let header = format!("# pecker {} v", self.kind);
+        let found: u32 = first
+            .and_then(|line| line.strip_prefix(&header))
+            .and_then(|v| v.trim().parse().ok())
+            .ok_or_else(|| invalid(format!("{}: missing `{header}N` header", path.display())))?;
+        if found > self.version {
+            return Err(invalid(format!(
+                "{}: version {found} is newer than the supported version {}, please upgrade pecker",
+                path.display(),
+                self.version
+            )));
+        }
+        Ok(found)
+    }
+
+    /// Add rows to the end of the file, which is created with the header if needed.
+    pub fn append(&self, rows: &[Vec<String>]) -> Result<()> {
+        let path = self.path()?;
+        let is_new = !path.is_file();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut content = if is_new { self.header() } else { String::new() };
+        push_rows(&mut content, rows);
+        file.write_all(content.as_bytes())
+    }
+
+    /// Replace the whole file with `rows` in the latest version.
+    ///
+    /// The rows go to a temporary file first, which then takes the place of the old one, so a
+    /// crash leaves either the old or the new file but never half of one.
+    pub fn save(&self, rows: &[Vec<String>]) -> Result<()> {
+        let path = self.path()?;
+        let mut content = self.header();
+        push_rows(&mut content, rows);
+        let tmp = path.with_extension("tsv.tmp");
+        let mut file = File::create(&tmp)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(tmp, path)
+    }
+}
+
+fn push_rows(content: &mut String, rows: &[Vec<String>]) {
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|f| escape(f)).collect();
+        content.push_str(&fields.join("\t"));
+        content.push('\n');
+    }
+}
+
+/// Escape a field of a tab separated file.
+pub fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+pub fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('t') => out.push('\t'),
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some(c) => out.push(c),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaped_fields_have_no_separators() {
+        let escaped = escape("a\tb\nc\rd");
+        assert!(!escaped.contains(['\t', '\n', '\r']));
+        assert_eq!(escaped, "a\\tb\\nc\\rd");
+    }
+
+    #[test]
+    fn escape_round_trip() {
+        for s in [
+            "",
+            "plain",
+            "tab\there",
+            "line\nbreak\r\n",
+            "back\\slash",
+            "\\t is not a tab",
+            "trailing\\",
+            "\\\\\t\\n\n",
+        ] {
+            assert_eq!(unescape(&escape(s)), s, "{s:?}");
+        }
+    }
+}
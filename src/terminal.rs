@@ -0,0 +1,75 @@
+use std::io::{stdout, Result};
+use std::panic::{self, PanicHookInfo};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crossterm::{
+    cursor,
+    event::{KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags},
+    execute,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
+type PanicHook = dyn Fn(&PanicHookInfo<'_>) + Sync + Send + 'static;
+
+/// Puts the terminal into raw mode on an alternate screen, and brings it back when dropped.
+///
+/// A panic hook is installed as well, so the shell is usable again even if pecker crashes.
+pub struct TerminalGuard {
+    /// Panic hook from before the guard, put back when it is dropped.
+    previous_hook: Arc<PanicHook>,
+}
+
+impl TerminalGuard {
+    pub fn new() -> Result<Self> {
+        let previous_hook: Arc<PanicHook> = Arc::from(panic::take_hook());
+        let hook = Arc::clone(&previous_hook);
+        panic::set_hook(Box::new(move |info| {
+            // best effort, the panic message matters more than a failed restore
+            let _ = restore();
+            hook(info);
+        }));
+        // the guard exists before the terminal is touched, so a failed step is undone on return
+        let guard = Self { previous_hook };
+
+        enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen, cursor::Show)?;
+        // ask for key release events, so that dwell and flight times can be measured
+        if supports_keyboard_enhancement().unwrap_or(false) {
+            execute!(
+                stdout(),
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+            )?;
+            KEYBOARD_ENHANCED.store(true, Ordering::SeqCst);
+        }
+
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore();
+        // hooks cannot be changed while unwinding, and the process is going down anyway
+        if !std::thread::panicking() {
+            let previous = Arc::clone(&self.previous_hook);
+            panic::set_hook(Box::new(move |info| previous(info)));
+        }
+    }
+}
+
+/// Restore the terminal to the state before pecker started.
+pub fn restore() -> Result<()> {
+    if KEYBOARD_ENHANCED.swap(false, Ordering::SeqCst) {
+        execute!(stdout(), PopKeyboardEnhancementFlags)?;
+    }
+    execute!(stdout(), cursor::Show, LeaveAlternateScreen)?;
+    disable_raw_mode()
+}
@@ -1,15 +1,45 @@
 use std::io::Result;
+use std::time::Duration;
 
 use crate::screen::{MainScreen, Styled};
 
 #[derive(Default)]
 pub struct TextLines {
-    raw_text: Vec<u8>,
-    lines: Vec<Vec<u8>>,
+    raw_text: Vec<char>,
+    lines: Vec<Vec<char>>,
     pub n_hit: usize,
     pub n_miss: usize,
     pub cursor_pos: (u16, u16),
     pub align_center: bool,
+    /// Whether wrong chars are passed over like correct ones, without showing which is which.
+    pub blind: bool,
+    words: Vec<Word>,
+    last_hit: Duration,
+    /// Position of the pace marker, in chars.
+    pace: Option<usize>,
+}
+
+/// A word of the text, along with how typing it went.
+#[derive(Debug, Clone, Default)]
+pub struct Word {
+    /// Range of the word in the text, in chars.
+    pub start: usize,
+    pub end: usize,
+    /// Wrong chars typed while at this word.
+    pub n_miss: usize,
+    /// Session time of the correct char before the word.
+    pub started: Option<Duration>,
+    /// Session time of the last char of the word.
+    pub finished: Option<Duration>,
+}
+
+impl Word {
+    /// Average time per char, once the word is typed.
+    #[must_use]
+    pub fn time_per_char(&self) -> Option<Duration> {
+        let time = self.finished?.saturating_sub(self.started?);
+        Some(time / (self.end - self.start) as u32)
+    }
 }
 
 pub enum State {
@@ -23,6 +53,7 @@ pub enum Expect {
     Char(char, usize),
     Softbreak,
     Backspace(usize),
+    Paused,
 }
 
 impl TextLines {
@@ -33,15 +64,20 @@ impl TextLines {
 
     pub fn reset(&mut self, text: Option<&str>, width: u16, align_center: bool) -> Expect {
         if let Some(text) = text {
-            self.raw_text = text.bytes().collect();
-            self.raw_text.push(b' ');
+            self.raw_text = text.chars().collect();
+            self.raw_text.push(' ');
+            self.words = split_words(&self.raw_text);
+            self.last_hit = Duration::ZERO;
+            self.pace = None;
             self.n_hit = 0;
             self.n_miss = 0;
             self.cursor_pos = (0, 0);
         }
 
-        let text_width =
-            (width - 8).min(((self.raw_text.len() as f32).sqrt().ceil() + 20.0) as u16);
+        let text_width = width
+            .saturating_sub(8)
+            .max(1)
+            .min(((self.raw_text.len() as f32).sqrt().ceil() + 20.0) as u16);
         self.lines = wrap_string(&self.raw_text, text_width);
         let mut n = self.n_hit + self.n_miss;
         let mut pos = (0u16, 0u16);
@@ -54,22 +90,50 @@ impl TextLines {
         self.align_center = align_center;
 
         if self.n_miss == 0 {
-            Expect::Char(self.current() as char, self.count_repeat())
+            Expect::Char(self.current(), self.count_repeat())
         } else {
             Expect::Backspace(self.n_miss)
         }
     }
 
+    #[must_use]
+    pub fn expect(&self) -> Expect {
+        if self.n_miss != 0 {
+            Expect::Backspace(self.n_miss)
+        } else if self.is_softbreak() {
+            Expect::Softbreak
+        } else {
+            Expect::Char(self.current(), self.count_repeat())
+        }
+    }
+
+    /// Ratio of the text typed correctly so far, in percent.
+    #[must_use]
+    pub fn progress(&self) -> f64 {
+        let total = self.raw_text.len().saturating_sub(1);
+        if total == 0 {
+            100.0
+        } else {
+            self.n_hit as f64 * 100.0 / total as f64
+        }
+    }
+
+    /// Whether the whole text has been typed.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.n_miss == 0 && self.n_hit == self.raw_text.len() - 1
+    }
+
     #[inline]
     #[must_use]
-    pub fn current(&self) -> u8 {
+    pub fn current(&self) -> char {
         self.raw_text[self.n_hit + self.n_miss]
     }
 
     #[must_use]
     pub fn count_repeat(&self) -> usize {
         let mut cnt = 1;
-        while self.raw_text[self.n_hit + self.n_miss + cnt] == self.current() {
+        while self.raw_text.get(self.n_hit + self.n_miss + cnt) == Some(&self.current()) {
             cnt += 1;
         }
         cnt
@@ -85,17 +149,22 @@ impl TextLines {
     #[inline]
     #[must_use]
     pub fn is_softbreak(&self) -> bool {
-        self.at_line_end() && self.current() == b' '
+        self.at_line_end() && self.current() == ' '
     }
 
     pub fn forward(&mut self, c: char) -> (State, Expect, bool) {
+        let mut missed = false;
         // do not move any further if already at the end
         if self.n_hit + self.n_miss != self.raw_text.len() - 1 {
             // check if matches
             if self.n_miss == 0
-                && (c as u8 == self.raw_text[self.n_hit] || self.is_softbreak() && c == '\n')
+                && (c == self.raw_text[self.n_hit] || self.is_softbreak() && c == '\n')
             {
                 self.n_hit += 1;
+            } else if self.blind {
+                // wrong chars are only revealed at the end
+                self.n_hit += 1;
+                missed = true;
             } else {
                 self.n_miss += 1;
             }
@@ -105,7 +174,7 @@ impl TextLines {
                 self.cursor_pos.1 += 1;
                 self.cursor_pos.0 = 0;
                 // skip leading spaces
-                while self.n_miss == 0 && self.current() == b' ' {
+                while self.n_miss == 0 && self.current() == ' ' {
                     self.cursor_pos.0 += 1;
                     self.n_hit += 1;
                 }
@@ -115,9 +184,9 @@ impl TextLines {
         }
 
         // output
-        let state = if self.n_miss > 0 {
+        let state = if self.n_miss > 0 || missed {
             State::Miss
-        } else if self.n_hit == self.raw_text.len() - 1 {
+        } else if self.is_finished() {
             State::End
         } else {
             State::Hit
@@ -127,13 +196,79 @@ impl TextLines {
         } else if self.is_softbreak() {
             Expect::Softbreak
         } else {
-            Expect::Char(self.current() as char, self.count_repeat())
+            Expect::Char(self.current(), self.count_repeat())
         };
         let redraw = self.cursor_pos.0 == 0;
 
         (state, expect, redraw)
     }
 
+    /// Record a typed char in the words, at the given session time.
+    ///
+    /// `position` is the char that was expected, a miss on a space counts against the word
+    /// before it.
+    pub fn record(&mut self, position: usize, hit: bool, time: Duration) {
+        let Some(i) = self
+            .words
+            .partition_point(|w| w.start <= position)
+            .checked_sub(1)
+        else {
+            if hit {
+                self.last_hit = time;
+            }
+            return;
+        };
+        let word = &mut self.words[i];
+        if !hit {
+            word.n_miss += 1;
+            return;
+        }
+        if position == word.start {
+            word.started = Some(self.last_hit);
+        }
+        if position + 1 == word.end {
+            word.finished = Some(time);
+        }
+        self.last_hit = time;
+    }
+
+    #[must_use]
+    pub fn words(&self) -> &[Word] {
+        &self.words
+    }
+
+    #[must_use]
+    pub fn word_text(&self, word: &Word) -> String {
+        self.raw_text[word.start..word.end].iter().collect()
+    }
+
+    /// Up to `n` distinct words that were mistyped, the most often first, then the slowest
+    /// of the rest.
+    #[must_use]
+    pub fn problem_words(&self, n: usize) -> Vec<String> {
+        let mut mistyped: Vec<&Word> = self.words.iter().filter(|w| w.n_miss > 0).collect();
+        mistyped.sort_by_key(|w| std::cmp::Reverse(w.n_miss));
+        let mut slow: Vec<(&Word, Duration)> = self
+            .words
+            .iter()
+            .filter(|w| w.n_miss == 0 && w.end - w.start > 1)
+            .filter_map(|w| Some((w, w.time_per_char()?)))
+            .collect();
+        slow.sort_by_key(|&(_, time)| std::cmp::Reverse(time));
+        // only the slowest quarter counts as slow
+        slow.truncate(slow.len() / 4);
+
+        let mut words: Vec<String> = Vec::new();
+        for word in mistyped.into_iter().chain(slow.into_iter().map(|(w, _)| w)) {
+            let text = self.word_text(word);
+            if words.len() < n && text.chars().any(char::is_alphanumeric) && !words.contains(&text)
+            {
+                words.push(text);
+            }
+        }
+        words
+    }
+
     pub fn backward(&mut self) -> (Expect, bool) {
         if self.n_miss != 0 {
             self.n_miss -= 1;
@@ -151,10 +286,7 @@ impl TextLines {
         } else if self.is_softbreak() {
             (Expect::Softbreak, true)
         } else {
-            (
-                Expect::Char(self.current() as char, self.count_repeat()),
-                false,
-            )
+            (Expect::Char(self.current(), self.count_repeat()), false)
         }
     }
 
@@ -171,12 +303,15 @@ impl TextLines {
         row: u16,
     ) -> Result<(u16, u16)> {
         let offset_x = if self.align_center {
-            (screen.width - self.lines[row as usize].len() as u16 - 1) / 2
+            screen
+                .width
+                .saturating_sub(self.lines[row as usize].len() as u16 + 1)
+                / 2
         } else {
             8
         };
         let offset_y = if self.align_center {
-            (screen.height - self.lines.len() as u16) / 2
+            screen.height.saturating_sub(self.lines.len() as u16) / 2
         } else {
             4
         };
@@ -188,49 +323,127 @@ impl TextLines {
         screen.clear()?;
         let mut n_hit = self.n_hit;
         let mut n_miss = self.n_miss;
+        let blind = self.blind;
 
         for i in 0..self.lines.len() {
             self.move_to(screen, 0, i as u16)?;
-            let line = std::str::from_utf8(&self.lines[i]).expect("string must be utf-8");
+            let line = &self.lines[i];
+            let part = |start: usize, end: usize| line[start..end].iter().collect::<String>();
+            let typed = |part: String| if blind { part.default() } else { part.hit() };
 
             if n_hit > 0 {
                 if line.len() <= n_hit {
                     n_hit -= line.len();
-                    screen.put(line.hit())?;
+                    screen.put(typed(part(0, line.len())))?;
                     continue;
                 }
-                screen.put(line[..n_hit].hit())?;
+                screen.put(typed(part(0, n_hit)))?;
             }
             if n_hit < line.len() && n_miss > 0 {
                 if line.len() - n_hit <= n_miss {
-                    screen.put(line[n_hit..].miss())?;
+                    screen.put(part(n_hit, line.len()).miss())?;
                     n_miss -= line.len() - n_hit;
                     n_hit = 0;
                     continue;
                 }
-                screen.put(line[n_hit..n_hit + n_miss].miss())?;
+                screen.put(part(n_hit, n_hit + n_miss).miss())?;
             }
             if n_miss + n_hit < line.len() {
-                screen.put(line[n_hit + n_miss..].blank())?;
+                screen.put(part(n_hit + n_miss, line.len()).blank())?;
                 n_hit = 0;
                 n_miss = 0;
             }
         }
+        if let Some(position) = self.pace {
+            self.draw_at(screen, position, true)?;
+        }
+
+        self.move_to_cursor(screen)?;
+        screen.flush()?;
+        Ok(())
+    }
+
+    /// Move the pace marker to a position, hiding it once past the end of the text.
+    pub fn set_pace(&mut self, screen: &mut MainScreen, position: Option<usize>) -> Result<()> {
+        let position = position.filter(|&p| p + 1 < self.raw_text.len());
+        if let Some(old) = self.pace.filter(|&old| Some(old) != position) {
+            self.draw_at(screen, old, false)?;
+        }
+        self.pace = position;
+        if let Some(position) = position {
+            self.draw_at(screen, position, true)?;
+        }
+        self.move_to_cursor(screen)?;
+        Ok(())
+    }
+
+    /// Draw the char at a position in the style of its state, or as the pace marker.
+    fn draw_at(&mut self, screen: &mut MainScreen, position: usize, pace: bool) -> Result<()> {
+        let mut column = position;
+        let mut row = 0;
+        while row < self.lines.len() && column >= self.lines[row].len() {
+            column -= self.lines[row].len();
+            row += 1;
+        }
+        let Some(&c) = self.lines.get(row).and_then(|line| line.get(column)) else {
+            return Ok(());
+        };
+        self.move_to(screen, column as u16, row as u16)?;
+        if pace {
+            screen.put(c.pace())
+        } else if position < self.n_hit && self.blind {
+            screen.put(c.default())
+        } else if position < self.n_hit {
+            screen.put(c.hit())
+        } else if position < self.n_hit + self.n_miss {
+            screen.put(c.miss())
+        } else {
+            screen.put(c.blank())
+        }
+    }
 
+    pub fn redraw_dimmed(&mut self, screen: &mut MainScreen) -> Result<()> {
+        screen.clear()?;
+        for i in 0..self.lines.len() {
+            self.move_to(screen, 0, i as u16)?;
+            let line: String = self.lines[i].iter().collect();
+            screen.put(line.dimmed())?;
+        }
         self.move_to_cursor(screen)?;
         screen.flush()?;
         Ok(())
     }
 }
 
-fn wrap_string(text: &[u8], width: u16) -> Vec<Vec<u8>> {
+/// Whitespace separated words of a text.
+fn split_words(text: &[char]) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.iter().enumerate() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                words.push(Word {
+                    start: s,
+                    end: i,
+                    ..Word::default()
+                });
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    words
+}
+
+fn wrap_string(text: &[char], width: u16) -> Vec<Vec<char>> {
     let mut lines = Vec::new();
     let mut current_line = Vec::new();
     let mut len = 0;
 
-    for word in text.split(|c| c == &b' ' || c == &b'\n') {
-        let c = if len == 0 { 0 } else { text[len - 1] };
-        if c == b'\n' || current_line.len() + word.len() > width as usize {
+    for word in text.split(|c| c == &' ' || c == &'\n') {
+        let c = if len == 0 { '\0' } else { text[len - 1] };
+        if c == '\n' || current_line.len() + word.len() > width as usize {
             lines.push(current_line);
             current_line = Vec::new();
         }
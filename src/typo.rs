@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use crate::{layout::Layout, stat::Typo};
+
+/// Why a wrong char was typed, as far as can be told from the text around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Kind {
+    /// The right key with the wrong Shift state, e.g. `a` for `A`.
+    WrongShift,
+    /// Two chars swapped, e.g. `hte` for `the`.
+    Transposition,
+    /// The previous char typed again, e.g. `thhe` for `the`.
+    Doubled,
+    /// A char skipped, e.g. `te` for `the`.
+    Omission,
+    /// An extra char typed, e.g. `thxe` for `the`.
+    Insertion,
+    /// A key next to the expected one.
+    Adjacent,
+    /// Any other wrong key.
+    Substitution,
+}
+
+impl Kind {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::WrongShift => "wrong shift",
+            Kind::Transposition => "transposed",
+            Kind::Doubled => "doubled",
+            Kind::Omission => "skipped",
+            Kind::Insertion => "inserted",
+            Kind::Adjacent => "adjacent key",
+            Kind::Substitution => "wrong key",
+        }
+    }
+}
+
+/// Classify a typo in `text`, looking at the wrong chars and the expected chars around it.
+#[must_use]
+pub fn classify(text: &[char], typo: &Typo, layout: &Layout) -> Kind {
+    let at = |offset: isize| {
+        typo.position
+            .checked_add_signed(offset)
+            .and_then(|i| text.get(i).copied())
+    };
+    let Some(expected) = at(0) else {
+        return Kind::Substitution;
+    };
+    let first = typo.typed[0];
+    let second = typo.typed.get(1).copied();
+    let expected_pos = layout.position(expected);
+    let typed_pos = layout.position(first);
+
+    if let (Some((col_e, row_e, shift_e)), Some((col_t, row_t, shift_t))) =
+        (expected_pos, typed_pos)
+    {
+        if (col_e, row_e) == (col_t, row_t) && shift_e != shift_t {
+            return Kind::WrongShift;
+        }
+    }
+    if Some(first) == at(1) && second == Some(expected) {
+        return Kind::Transposition;
+    }
+    if typo.position > 0 && Some(first) == at(-1) {
+        return Kind::Doubled;
+    }
+    if Some(first) == at(1) && second.is_none_or(|c| Some(c) == at(2)) {
+        return Kind::Omission;
+    }
+    if second == Some(expected) {
+        return Kind::Insertion;
+    }
+    if let (Some((col_e, row_e, shift_e)), Some((col_t, row_t, shift_t))) =
+        (expected_pos, typed_pos)
+    {
+        let thumb = col_e == 0 || col_t == 0;
+        // columns count outwards from 1 on each hand, so the left ones move up to close the gap
+        let continuous = |col: i16| if col < 0 { col + 1 } else { col };
+        if !thumb
+            && shift_e == shift_t
+            && (continuous(col_e) - continuous(col_t)).abs() <= 1
+            && (row_e - row_t).abs() <= 1
+        {
+            return Kind::Adjacent;
+        }
+    }
+    Kind::Substitution
+}
+
+/// Number of typos of each kind in a session over `text`.
+#[must_use]
+pub fn breakdown(text: &str, typos: &[Typo], layout: &Layout) -> BTreeMap<Kind, usize> {
+    let text: Vec<char> = text.chars().collect();
+    let mut kinds = BTreeMap::new();
+    for typo in typos {
+        *kinds.entry(classify(&text, typo, layout)).or_default() += 1;
+    }
+    kinds
+}
+
+/// A line for the results screen, the most frequent kinds first.
+#[must_use]
+pub fn summary(kinds: &BTreeMap<Kind, usize>) -> Option<String> {
+    if kinds.is_empty() {
+        return None;
+    }
+    let mut kinds: Vec<(Kind, usize)> = kinds.iter().map(|(&k, &n)| (k, n)).collect();
+    kinds.sort_by_key(|&(kind, n)| (std::cmp::Reverse(n), kind));
+    let cells: Vec<String> = kinds
+        .iter()
+        .map(|(kind, n)| format!("{n} {}", kind.name()))
+        .collect();
+    Some(format!("typos: {}", cells.join(" | ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(text: &str, position: usize, typed: char) -> Kind {
+        let text: Vec<char> = text.chars().collect();
+        let typo = Typo {
+            position,
+            typed: vec![typed],
+        };
+        classify(&text, &typo, &Layout::new())
+    }
+
+    #[test]
+    fn adjacent_on_the_same_hand() {
+        assert_eq!(kind("dog", 0, 'f'), Kind::Adjacent);
+        assert_eq!(kind("lot", 0, 'k'), Kind::Adjacent);
+    }
+
+    #[test]
+    fn adjacent_across_the_centre() {
+        assert_eq!(kind("hat", 0, 'g'), Kind::Adjacent);
+        assert_eq!(kind("yes", 0, 't'), Kind::Adjacent);
+        assert_eq!(kind("not", 0, 'b'), Kind::Adjacent);
+    }
+
+    #[test]
+    fn far_keys_are_substitutions() {
+        assert_eq!(kind("pot", 0, 'a'), Kind::Substitution);
+        assert_eq!(kind("hat", 0, 'f'), Kind::Substitution);
+    }
+}
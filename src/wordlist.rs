@@ -0,0 +1,186 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::storage::data_subdir;
+
+const ENGLISH: &str = include_str!("wordlists/google-10000-english-usa-no-swears.txt");
+
+/// Built-in word lists as `(name, description, words, number of words to use)`.
+const BUILTIN: &[(&str, &str, &str, usize)] = &[
+    ("english", "top 1000 English words", ENGLISH, 1000),
+    ("english-200", "top 200 English words", ENGLISH, 200),
+    (
+        "english-10k",
+        "top 10000 English words",
+        ENGLISH,
+        usize::MAX,
+    ),
+    (
+        "german",
+        "common German words",
+        include_str!("wordlists/german.txt"),
+        usize::MAX,
+    ),
+    (
+        "french",
+        "common French words",
+        include_str!("wordlists/french.txt"),
+        usize::MAX,
+    ),
+    (
+        "spanish",
+        "common Spanish words",
+        include_str!("wordlists/spanish.txt"),
+        usize::MAX,
+    ),
+    (
+        "portuguese",
+        "common Portuguese words",
+        include_str!("wordlists/portuguese.txt"),
+        usize::MAX,
+    ),
+    (
+        "italian",
+        "common Italian words",
+        include_str!("wordlists/italian.txt"),
+        usize::MAX,
+    ),
+    (
+        "dutch",
+        "common Dutch words",
+        include_str!("wordlists/dutch.txt"),
+        usize::MAX,
+    ),
+    (
+        "programming",
+        "keywords and identifiers of popular programming languages",
+        include_str!("wordlists/programming.txt"),
+        usize::MAX,
+    ),
+    (
+        "bigrams",
+        "most common English letter pairs",
+        include_str!("wordlists/bigrams.txt"),
+        usize::MAX,
+    ),
+];
+
+pub const DEFAULT: &str = "english";
+
+/// A list of words to build random texts from.
+pub struct WordList {
+    pub name: String,
+    pub words: Vec<String>,
+}
+
+impl WordList {
+    /// Load a word list by name, looking at built-in lists, then registered ones,
+    /// and finally treating `name` as a path to a file.
+    pub fn load(name: &str) -> Result<Self> {
+        if let Some(&(_, _, words, take)) = BUILTIN.iter().find(|(n, ..)| *n == name) {
+            return Ok(Self::parse(name, words, take));
+        }
+        let registered = registered_path(name)?;
+        let path = if registered.is_file() {
+            registered
+        } else if Path::new(name).is_file() {
+            PathBuf::from(name)
+        } else {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("unknown word list `{name}`"),
+            ));
+        };
+        let list = Self::parse(name, &fs::read_to_string(path)?, usize::MAX);
+        if list.words.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("word list `{name}` is empty"),
+            ));
+        }
+        Ok(list)
+    }
+
+    /// Words are separated by whitespace, and lines starting with `#` are comments.
+    fn parse(name: &str, text: &str, take: usize) -> Self {
+        let mut seen = HashSet::new();
+        let words = text
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .flat_map(str::split_whitespace)
+            .filter(|word| seen.insert(*word))
+            .take(take)
+            .map(str::to_string)
+            .collect();
+        Self {
+            name: name.to_string(),
+            words,
+        }
+    }
+
+    /// Pick `n` distinct words at random, or all of them if the list is shorter.
+    pub fn sample<R: Rng>(&self, rng: &mut R, n: usize) -> Vec<&str> {
+        self.words
+            .choose_multiple(rng, n)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+fn registered_path(name: &str) -> Result<PathBuf> {
+    Ok(data_subdir("wordlists")?.join(format!("{name}.txt")))
+}
+
+/// Copy a word list file into the data directory, so it can be selected by name later.
+pub fn register(name: &str, file: &Path) -> Result<PathBuf> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "word list names may only contain letters, digits, `-` and `_`",
+        ));
+    }
+    if BUILTIN.iter().any(|(n, ..)| *n == name) {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("`{name}` is a built-in word list"),
+        ));
+    }
+    let list = WordList::parse(name, &fs::read_to_string(file)?, usize::MAX);
+    if list.words.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("no words found in {}", file.display()),
+        ));
+    }
+    let path = registered_path(name)?;
+    fs::write(&path, list.words.join("\n") + "\n")?;
+    Ok(path)
+}
+
+/// Names and descriptions of all available word lists.
+pub fn available() -> Result<Vec<(String, String)>> {
+    let mut lists: Vec<(String, String)> = BUILTIN
+        .iter()
+        .map(|(name, description, ..)| (name.to_string(), description.to_string()))
+        .collect();
+    let mut registered = Vec::new();
+    for entry in fs::read_dir(data_subdir("wordlists")?)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+                registered.push((name.to_string(), "registered".to_string()));
+            }
+        }
+    }
+    registered.sort();
+    lists.extend(registered);
+    Ok(lists)
+}